@@ -1,58 +1,59 @@
-use crate::{schema::OwnedField, Client, Error, Typesense};
-use std::{iter::once, marker::PhantomData};
+use crate::{schema::OwnedField, Client, Error, Reqwest, Transport, Typesense};
+use std::{borrow::Cow, iter::once, marker::PhantomData, time::Duration};
 use tracing::instrument;
 
 use super::{documents::Documents, CollectionResponse, CollectionUpdate};
 
-const PATH: &'static str = "collections";
+const PATH: &str = "collections";
 
 #[derive(Debug, Clone)]
-pub struct Collection<'a, T: Typesense> {
-    client: &'a Client,
+pub struct Collection<'a, T: Typesense, H: Transport = Reqwest> {
+    client: Cow<'a, Client<H>>,
     collection_name: &'a str,
     _phantom: PhantomData<T>,
 }
 
-impl<'a, T: Typesense> Collection<'a, T> {
-    pub(crate) fn new(client: &'a Client, collection_name: &'a str) -> Collection<'a, T> {
+impl<'a, T: Typesense, H: Transport> Collection<'a, T, H> {
+    pub(crate) fn new(client: &'a Client<H>, collection_name: &'a str) -> Collection<'a, T, H> {
         Self {
-            client,
+            client: Cow::Borrowed(client),
             collection_name,
             _phantom: PhantomData,
         }
     }
 
+    /// Overrides the timeouts of the client for requests on this collection.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.client = Cow::Owned(self.client.with_timeout(timeout));
+        self
+    }
+
     #[instrument(skip(self))]
-    pub async fn documents(&self) -> Documents<'_, T> {
-        Documents::new(self.client, self.collection_name.as_ref())
+    pub async fn documents(&self) -> Documents<'_, T, H> {
+        Documents::new(&self.client, self.collection_name)
     }
 
     #[instrument(skip(self))]
     pub async fn retreive(&self) -> Result<CollectionResponse, Error> {
-        self.client.get([PATH, self.collection_name.as_ref()]).await
+        self.client.get([PATH, self.collection_name]).await
     }
 
     #[instrument(skip(self))]
     pub async fn create(&self) -> Result<CollectionResponse, Error> {
         self.client
-            .post((&T::schema(self.collection_name.as_ref()), once(PATH)))
+            .post((&T::schema(self.collection_name), once(PATH)))
             .await
     }
 
     #[instrument(skip(self))]
     pub async fn update(&self, fields: Vec<OwnedField>) -> Result<CollectionUpdate, Error> {
         self.client
-            .patch((
-                CollectionUpdate { fields },
-                [PATH, self.collection_name.as_ref()],
-            ))
+            .patch((CollectionUpdate { fields }, [PATH, self.collection_name]))
             .await
     }
 
     #[instrument(skip(self))]
     pub async fn delete(&self) -> Result<CollectionResponse, Error> {
-        self.client
-            .delete([PATH, self.collection_name.as_ref()])
-            .await
+        self.client.delete([PATH, self.collection_name]).await
     }
 }
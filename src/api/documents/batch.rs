@@ -1,35 +1,38 @@
-use crate::Typesense;
+use crate::{Reqwest, Transport, Typesense};
 
 use super::{BatchResult, Documents};
 use std::{
     future::{Future, IntoFuture},
     marker::PhantomData,
+    time::Duration,
 };
 
 #[derive(Debug)]
-pub struct DocumentBatchAction<'a, T: Typesense, Fut: 'a> {
-    api: &'a Documents<'a, T>,
+pub struct DocumentBatchAction<'a, T: Typesense, Fut: 'a, H: Transport = Reqwest> {
+    api: &'a Documents<'a, T, H>,
     documents: &'a [T::Partial],
     action: Option<&'a str>,
     dirty_values: Option<&'a str>,
     batch_size: Option<&'a str>,
+    timeout: Option<Duration>,
     fut: Fut,
     _phantom: PhantomData<Fut>,
 }
 
-impl<'a, T: Typesense, Fut: 'a> DocumentBatchAction<'a, T, Fut> {
+impl<'a, T: Typesense, Fut: 'a, H: Transport> DocumentBatchAction<'a, T, Fut, H> {
     pub(crate) fn new(
-        api: &'a Documents<'a, T>,
+        api: &'a Documents<'a, T, H>,
         action: Option<&'a str>,
         documents: &'a [T::Partial],
         fut: Fut,
-    ) -> DocumentBatchAction<'a, T, Fut> {
+    ) -> DocumentBatchAction<'a, T, Fut, H> {
         DocumentBatchAction {
             api,
             documents,
             action,
             dirty_values: None,
             batch_size: None,
+            timeout: None,
             fut,
             _phantom: PhantomData,
         }
@@ -38,7 +41,7 @@ impl<'a, T: Typesense, Fut: 'a> DocumentBatchAction<'a, T, Fut> {
     pub fn dirty_values(
         mut self,
         dirty_values: &'a str,
-    ) -> DocumentBatchAction<'a, T, impl 'a + Future<Output = BatchResult>> {
+    ) -> DocumentBatchAction<'a, T, impl 'a + Future<Output = BatchResult>, H> {
         self.dirty_values.replace(dirty_values);
         self.reset()
     }
@@ -46,18 +49,28 @@ impl<'a, T: Typesense, Fut: 'a> DocumentBatchAction<'a, T, Fut> {
     pub fn batch_size(
         mut self,
         batch_size: &'a str,
-    ) -> DocumentBatchAction<'a, T, impl 'a + Future<Output = BatchResult>> {
+    ) -> DocumentBatchAction<'a, T, impl 'a + Future<Output = BatchResult>, H> {
         self.batch_size.replace(batch_size);
         self.reset()
     }
 
-    fn reset(self) -> DocumentBatchAction<'a, T, impl 'a + Future<Output = BatchResult>> {
+    /// Overrides the import timeout of the client for this request.
+    pub fn timeout(
+        mut self,
+        timeout: Duration,
+    ) -> DocumentBatchAction<'a, T, impl 'a + Future<Output = BatchResult>, H> {
+        self.timeout.replace(timeout);
+        self.reset()
+    }
+
+    fn reset(self) -> DocumentBatchAction<'a, T, impl 'a + Future<Output = BatchResult>, H> {
         let Self {
             api,
             documents,
             action,
             dirty_values,
             batch_size,
+            timeout,
             ..
         } = self;
 
@@ -67,12 +80,22 @@ impl<'a, T: Typesense, Fut: 'a> DocumentBatchAction<'a, T, Fut> {
             ("batch_size", batch_size),
         ];
 
-        DocumentBatchAction::new(api, action, documents, api.batch_action(query, documents))
+        let mut ret = DocumentBatchAction::new(
+            api,
+            action,
+            documents,
+            api.batch_action(query, documents, timeout),
+        );
+        ret.dirty_values = dirty_values;
+        ret.batch_size = batch_size;
+        ret.timeout = timeout;
+
+        ret
     }
 }
 
-impl<'a, T: Typesense, Fut: 'a + Future<Output = BatchResult>> IntoFuture
-    for DocumentBatchAction<'a, T, Fut>
+impl<'a, T: Typesense, Fut: 'a + Future<Output = BatchResult>, H: Transport> IntoFuture
+    for DocumentBatchAction<'a, T, Fut, H>
 {
     type Output = Fut::Output;
     type IntoFuture = Fut;
@@ -1,7 +1,17 @@
-use super::ImportResponse;
-use crate::{error::*, Client, Error, Typesense};
+use super::{
+    search::{CollectionSearch, MultiSearchRequest, SearchQuery},
+    ImportResponse, MultiSearchResponse, SearchResponse,
+};
+use crate::{error::*, Client, Error, Reqwest, Transport, Typesense};
 use bytes::{BufMut, BytesMut};
-use std::{future::Future, io::Write, marker::PhantomData};
+use serde::de::DeserializeOwned;
+use std::{
+    borrow::{Borrow, Cow},
+    future::Future,
+    io::Write,
+    marker::PhantomData,
+    time::Duration,
+};
 use tracing::instrument;
 
 type BatchResult = Result<(), Error>;
@@ -11,43 +21,43 @@ mod batch;
 pub use batch::*;
 
 #[derive(Debug, Clone)]
-pub struct Documents<'a, T: Typesense> {
-    client: &'a Client,
+pub struct Documents<'a, T: Typesense, H: Transport = Reqwest> {
+    client: Cow<'a, Client<H>>,
     collection_name: &'a str,
     _phantom: PhantomData<T>,
 }
 
-impl<'a, T: Typesense> Documents<'a, T> {
-    pub(crate) fn new(client: &'a Client, collection_name: &'a str) -> Documents<'a, T> {
+impl<'a, T: Typesense, H: Transport> Documents<'a, T, H> {
+    pub(crate) fn new(client: &'a Client<H>, collection_name: &'a str) -> Documents<'a, T, H> {
         Self {
-            client,
+            client: Cow::Borrowed(client),
             collection_name,
             _phantom: PhantomData,
         }
     }
 
-    pub(crate) fn client(&self) -> &Client {
+    pub(crate) fn client(&self) -> &Client<H> {
         &self.client
     }
 
+    /// Overrides the timeouts of the client for requests on these documents.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.client = Cow::Owned(self.client.with_timeout(timeout));
+        self
+    }
+
     #[instrument(skip(self))]
     pub async fn create(&self, document: &T) -> Result<T::Partial, Error> {
         self.client()
-            .post((
-                document,
-                ["collections", self.collection_name.as_ref(), "documents"],
-            ))
+            .post((document, ["collections", self.collection_name, "documents"]))
             .await
     }
 
-    #[instrument(skip(self))]
-    pub async fn retrieve(&self, id: &str) -> Result<T, Error> {
-        let path = [
-            "collections",
-            self.collection_name.as_ref(),
-            "documents",
-            id,
-        ];
+    /// Takes the id by value or by reference, i.e. `retrieve(9780441013593)` or `retrieve(&id)`.
+    #[instrument(skip(self, id), fields(id = %id.borrow()))]
+    pub async fn retrieve(&self, id: impl Borrow<T::Id>) -> Result<T, Error> {
+        let id = id.borrow().to_string();
+        let path = ["collections", self.collection_name, "documents", &id];
 
         let ret = self.client().get(path).await?;
 
@@ -56,57 +66,115 @@ impl<'a, T: Typesense> Documents<'a, T> {
 
     #[instrument(skip(self))]
     pub async fn upsert(&self, document: &T) -> Result<T::Partial, Error> {
-        let path = ["collections", self.collection_name.as_ref(), "documents"];
+        let path = ["collections", self.collection_name, "documents"];
 
         self.client()
             .post((document, path, [("action", Some("upsert"))]))
             .await
     }
 
-    #[instrument(skip(self))]
-    pub async fn update(&self, id: &str, document: &T::Partial) -> Result<T::Partial, Error> {
-        let path = [
-            "collections",
-            self.collection_name.as_ref(),
-            "documents",
-            id,
-        ];
+    #[instrument(skip(self, id), fields(id = %id.borrow()))]
+    pub async fn update(
+        &self,
+        id: impl Borrow<T::Id>,
+        document: &T::Partial,
+    ) -> Result<T::Partial, Error> {
+        let id = id.borrow().to_string();
+        let path = ["collections", self.collection_name, "documents", &id];
 
         self.client().patch((document, path)).await
     }
 
+    /// Updates the stored document with the id of `document`.
     #[instrument(skip(self))]
-    pub async fn delete(&self, id: &str) -> Result<T, Error> {
-        let path = [
-            "collections",
-            self.collection_name.as_ref(),
-            "documents",
-            id,
-        ];
+    pub async fn update_document(&self, document: &T) -> Result<T::Partial, Error> {
+        let id = document.id().ok_or(Error::DocumentIdNotFound)?.to_string();
+        let path = ["collections", self.collection_name, "documents", &id];
+
+        self.client().patch((document, path)).await
+    }
+
+    #[instrument(skip(self, id), fields(id = %id.borrow()))]
+    pub async fn delete(&self, id: impl Borrow<T::Id>) -> Result<T, Error> {
+        let id = id.borrow().to_string();
+        let path = ["collections", self.collection_name, "documents", &id];
 
         self.client().delete(path).await
     }
 
+    /// Every document of the collection.
+    #[instrument(skip(self))]
+    pub async fn export(&self) -> Result<Vec<T>, Error> {
+        let path = ["collections", self.collection_name, "documents", "export"];
+
+        self.client().get_lines(path).await
+    }
+
+    #[instrument(skip(self))]
+    pub async fn search(&self, query: &SearchQuery) -> Result<SearchResponse<T>, Error> {
+        self.search_joined(query).await
+    }
+
+    /// Search, deserializing documents joined from other collections into `J`.
+    ///
+    /// See [`SearchHit`](super::SearchHit) for how joined documents are deserialized.
+    #[instrument(skip(self))]
+    pub async fn search_joined<J: DeserializeOwned>(
+        &self,
+        query: &SearchQuery,
+    ) -> Result<SearchResponse<T, J>, Error> {
+        let path = ["collections", self.collection_name, "documents", "search"];
+
+        self.client().get_with_query(path, query).await
+    }
+
+    /// Runs multiple searches against this collection in a single `POST /multi_search` request.
+    ///
+    /// Search parameters are sent in the body, so this is also the way to send
+    /// vector queries with large vectors.
+    #[instrument(skip(self))]
+    pub async fn multi_search(
+        &self,
+        queries: &[SearchQuery],
+    ) -> Result<MultiSearchResponse<T>, Error> {
+        let body = MultiSearchRequest {
+            searches: queries
+                .iter()
+                .map(|query| CollectionSearch {
+                    collection: self.collection_name,
+                    query,
+                })
+                .collect(),
+        };
+
+        self.client().post((body, ["multi_search"])).await
+    }
+
     #[instrument(skip(self, documents))]
     pub fn batch_create(
         &'a self,
         documents: &'a [T::Partial],
-    ) -> DocumentBatchAction<'a, T, impl 'a + Future<Output = BatchResult>> {
-        DocumentBatchAction::new(self, None, documents, self.batch_action([], documents))
+    ) -> DocumentBatchAction<'a, T, impl 'a + Future<Output = BatchResult>, H> {
+        DocumentBatchAction::new(
+            self,
+            None,
+            documents,
+            self.batch_action([], documents, None),
+        )
     }
 
     #[instrument(skip(self, documents))]
     pub fn batch_upsert(
         &'a self,
         documents: &'a [T::Partial],
-    ) -> DocumentBatchAction<'a, T, impl 'a + Future<Output = BatchResult>> {
+    ) -> DocumentBatchAction<'a, T, impl 'a + Future<Output = BatchResult>, H> {
         let action = Some("upsert");
 
         DocumentBatchAction::new(
             self,
             action,
             documents,
-            self.batch_action([("action", action)], documents),
+            self.batch_action([("action", action)], documents, None),
         )
     }
 
@@ -114,14 +182,14 @@ impl<'a, T: Typesense> Documents<'a, T> {
     pub fn batch_update(
         &'a self,
         documents: &'a [T::Partial],
-    ) -> DocumentBatchAction<'a, T, impl 'a + Future<Output = BatchResult>> {
+    ) -> DocumentBatchAction<'a, T, impl 'a + Future<Output = BatchResult>, H> {
         let action = Some("update");
 
         DocumentBatchAction::new(
             self,
             action,
             documents,
-            self.batch_action([("action", action)], documents),
+            self.batch_action([("action", action)], documents, None),
         )
     }
 
@@ -129,14 +197,14 @@ impl<'a, T: Typesense> Documents<'a, T> {
     pub fn batch_emplace(
         &'a self,
         documents: &'a [T::Partial],
-    ) -> DocumentBatchAction<'a, T, impl 'a + Future<Output = BatchResult>> {
+    ) -> DocumentBatchAction<'a, T, impl 'a + Future<Output = BatchResult>, H> {
         let action = Some("emplace");
 
         DocumentBatchAction::new(
             self,
             action,
             documents,
-            self.batch_action([("action", action)], documents),
+            self.batch_action([("action", action)], documents, None),
         )
     }
 
@@ -145,32 +213,27 @@ impl<'a, T: Typesense> Documents<'a, T> {
         &'a self,
         query: QueryPair<'a, N>,
         documents: &'a [T::Partial],
+        timeout: Option<Duration>,
     ) -> BatchResult {
-        let path = [
-            "collections",
-            self.collection_name.as_ref(),
-            "documents",
-            "import",
-        ];
+        let path = ["collections", self.collection_name, "documents", "import"];
 
         let mut writer = BytesMut::new().writer();
 
         for document in documents {
             serde_json::to_writer(&mut writer, document)
                 .toss_document_to_json_with(|| format!("{document:?}"))?;
-            writer.write(&[b'\n']).expect("does not return Err ever");
+            writer.write_all(b"\n").expect("does not return Err ever");
         }
 
         let action = query
             .iter()
             .find(|q| q.0 == "action")
-            .map(|q| q.1)
-            .flatten()
+            .and_then(|q| q.1)
             .unwrap_or("create");
 
         let body = self
             .client()
-            .post_raw(path, writer.into_inner(), query)
+            .post_raw(path, writer.into_inner(), query, timeout)
             .await?;
 
         // info!("body: {body}");
@@ -178,7 +241,7 @@ impl<'a, T: Typesense> Documents<'a, T> {
         let mut res = Vec::with_capacity(documents.len());
 
         for line in body.lines() {
-            res.push(serde_json::from_str(&line).toss_deserialize_text_with(|| line.to_owned())?);
+            res.push(serde_json::from_str(line).toss_deserialize_text_with(|| line.to_owned())?);
         }
 
         import_into_res(action, res)
@@ -210,7 +273,7 @@ mod tests {
 
     #[test]
     fn test_actions() {
-        let client = Client::new("hostname", "xyz");
+        let _client = Client::new("hostname", "xyz");
         // client
         //     .collection()
         //     .documents()
@@ -0,0 +1,115 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::{convert::Infallible, fmt, str::FromStr};
+
+macro_rules! actions {
+    ($($(#[$doc:meta])* $variant:ident => $s:literal),* $(,)?) => {
+        /// Action an API key is allowed to perform, i.e. `documents:search`.
+        ///
+        /// Actions not listed here can be given with [`Action::Custom`].
+        #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+        pub enum Action {
+            $(
+                $(#[$doc])*
+                $variant,
+            )*
+            Custom(String),
+        }
+
+        impl Action {
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $(Self::$variant => $s,)*
+                    Self::Custom(s) => s,
+                }
+            }
+        }
+
+        impl FromStr for Action {
+            type Err = Infallible;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                Ok(match s {
+                    $($s => Self::$variant,)*
+                    s => Self::Custom(s.to_owned()),
+                })
+            }
+        }
+    };
+}
+
+actions!(
+    /// every action on every resource
+    All => "*",
+    CollectionsAll => "collections:*",
+    CollectionsCreate => "collections:create",
+    CollectionsDelete => "collections:delete",
+    CollectionsGet => "collections:get",
+    CollectionsList => "collections:list",
+    DocumentsAll => "documents:*",
+    DocumentsSearch => "documents:search",
+    DocumentsGet => "documents:get",
+    DocumentsCreate => "documents:create",
+    DocumentsUpsert => "documents:upsert",
+    DocumentsUpdate => "documents:update",
+    DocumentsDelete => "documents:delete",
+    DocumentsImport => "documents:import",
+    DocumentsExport => "documents:export",
+    AliasesAll => "aliases:*",
+    AliasesCreate => "aliases:create",
+    AliasesDelete => "aliases:delete",
+    AliasesGet => "aliases:get",
+    AliasesList => "aliases:list",
+    SynonymsAll => "synonyms:*",
+    OverridesAll => "overrides:*",
+    KeysAll => "keys:*",
+    KeysCreate => "keys:create",
+    KeysDelete => "keys:delete",
+    KeysGet => "keys:get",
+    KeysList => "keys:list",
+    MetricsList => "metrics.json:list",
+    StatsList => "stats.json:list",
+    DebugList => "debug:list",
+);
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Serialize for Action {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Action {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        Ok(s.parse().unwrap_or_else(|e| match e {}))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_action_serde() {
+        let actions = vec![
+            Action::DocumentsSearch,
+            Action::All,
+            Action::Custom("analytics/rules:list".to_owned()),
+        ];
+
+        let json = serde_json::to_string(&actions).unwrap();
+        assert_eq!(json, r#"["documents:search","*","analytics/rules:list"]"#);
+        assert_eq!(serde_json::from_str::<Vec<Action>>(&json).unwrap(), actions);
+    }
+}
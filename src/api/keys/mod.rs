@@ -1,143 +1,153 @@
-use crate::{Client, Error};
-use base64::Engine;
-use hmac::{Hmac, Mac};
+use crate::{Client, Error, Reqwest, Transport};
 use serde::{Deserialize, Serialize};
-use serde_with::skip_serializing_none;
-use sha2::Sha256;
+use serde_with::{serde_as, skip_serializing_none, TimestampSeconds};
 use std::{
+    borrow::Cow,
     iter::once,
     time::{Duration, SystemTime},
 };
 use tracing::instrument;
 
-const PATH: &'static str = "keys";
+mod action;
+mod scoped;
+pub use action::*;
+pub use scoped::*;
 
+const PATH: &str = "keys";
+
+/// API key to create.
+///
+/// `collections` are matched as regular expressions against collection names,
+/// i.e. `*` for every collection, or `org_.*` for every collection starting with `org_`.
 #[skip_serializing_none]
+#[serde_as]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ApiKey {
+    pub id: Option<usize>,
     pub description: String,
-    pub actions: Vec<String>,
+    pub actions: Vec<Action>,
     pub collections: Vec<String>,
     pub value: Option<String>,
-    pub expires_at: Option<u64>,
-}
-
-#[derive(Debug, Clone, Copy)]
-pub struct Keys<'a> {
-    client: &'a Client,
+    #[serde_as(as = "Option<TimestampSeconds<i64>>")]
+    pub expires_at: Option<SystemTime>,
+    pub autodelete: Option<bool>,
 }
 
-impl<'a> Keys<'a> {
-    pub(crate) fn new(client: &'a Client) -> Keys<'a> {
-        Self { client }
+impl ApiKey {
+    pub fn new<T: ToString>(
+        description: impl ToString,
+        actions: impl IntoIterator<Item = Action>,
+        collections: impl IntoIterator<Item = T>,
+    ) -> Self {
+        Self {
+            id: None,
+            description: description.to_string(),
+            actions: actions.into_iter().collect(),
+            collections: collections.into_iter().map(|c| c.to_string()).collect(),
+            value: None,
+            expires_at: None,
+            autodelete: None,
+        }
     }
 
-    #[instrument]
-    pub async fn create(&self, key: &ApiKey) -> Result<ApiKey, Error> {
-        self.client.post((key, once(PATH))).await
+    /// Key that can only search documents of `collections`.
+    pub fn search_only<T: ToString>(collections: impl IntoIterator<Item = T>) -> Self {
+        Self::new("search only key", [Action::DocumentsSearch], collections)
     }
 
-    #[instrument]
-    pub async fn retreive(&self, id: &str) -> Result<ApiKeyResponse, Error> {
-        self.client.get([PATH, id].into_iter()).await
+    /// Key that can perform every action on every collection.
+    pub fn admin() -> Self {
+        Self::new("admin key", [Action::All], ["*"])
     }
 
-    #[instrument]
-    pub async fn retreive_all(&self) -> Result<ApiKeyResponses, Error> {
-        self.client.get(once(PATH)).await
+    pub fn description(mut self, description: impl ToString) -> Self {
+        self.description = description.to_string();
+        self
     }
 
-    #[instrument]
-    pub async fn delete(&self, id: &str) -> Result<ApiKeyDeleteResponse, Error> {
-        self.client.delete([PATH, id].into_iter()).await
+    pub fn action(mut self, action: Action) -> Self {
+        self.actions.push(action);
+        self
     }
-}
 
-pub fn generate_scoped_search_key(
-    key: impl AsRef<str>,
-    expire_in: Duration,
-) -> GenerateScopedSearchKeyBuilder {
-    GenerateScopedSearchKeyBuilder {
-        key: key.as_ref().to_owned(),
-        ..Default::default()
+    pub fn collection(mut self, collection: impl ToString) -> Self {
+        self.collections.push(collection.to_string());
+        self
     }
-    .expire_in(expire_in)
-}
 
-#[derive(Debug, Default, Clone)]
-pub struct GenerateScopedSearchKeyBuilder {
-    key: String,
-    filters: ScopedSearchFilters,
-}
-
-#[derive(Debug, Default, Clone, Serialize)]
-#[skip_serializing_none]
-pub struct ScopedSearchFilters {
-    query_by: Option<String>,
-    filter_by: Option<String>,
-    exclude_fields: Option<String>,
-    include_fields: Option<String>,
-    limit_hits: Option<usize>,
-    expires_at: u64,
-}
+    /// Allow every collection whose name starts with `prefix`.
+    pub fn collection_prefix(self, prefix: &str) -> Self {
+        let mut pattern = String::with_capacity(prefix.len() + 2);
 
-impl GenerateScopedSearchKeyBuilder {
-    pub fn expire_in(mut self, expire_in: Duration) -> Self {
-        let now = SystemTime::now()
-            .duration_since(SystemTime::UNIX_EPOCH)
-            .unwrap();
-        let expire_at = now + expire_in;
-        self.filters.expires_at = expire_at.as_secs();
+        for c in prefix.chars() {
+            if "\\.^$|?*+()[]{}".contains(c) {
+                pattern.push('\\');
+            }
+            pattern.push(c);
+        }
+        pattern.push_str(".*");
 
-        self
+        self.collection(pattern)
     }
 
-    pub fn query_by(mut self, query_by: String) -> Self {
-        self.filters.query_by.replace(query_by);
-
+    /// Use a custom value for the key instead of a generated one.
+    pub fn value(mut self, value: impl ToString) -> Self {
+        self.value.replace(value.to_string());
         self
     }
 
-    pub fn filter_by(mut self, filter_by: String) -> Self {
-        self.filters.filter_by.replace(filter_by);
-
+    pub fn expires_at(mut self, expires_at: SystemTime) -> Self {
+        self.expires_at.replace(expires_at);
         self
     }
 
-    pub fn limit_hits(mut self, limit_hits: usize) -> Self {
-        self.filters.limit_hits.replace(limit_hits);
+    pub fn expires_in(self, expires_in: Duration) -> Self {
+        self.expires_at(SystemTime::now() + expires_in)
+    }
 
+    /// Delete the key from typesense once it expires.
+    pub fn autodelete(mut self, autodelete: bool) -> Self {
+        self.autodelete.replace(autodelete);
         self
     }
+}
 
-    pub fn include_fields(mut self, fields: String) -> Self {
-        self.filters.include_fields.replace(fields);
+#[derive(Debug, Clone)]
+pub struct Keys<'a, H: Transport = Reqwest> {
+    client: Cow<'a, Client<H>>,
+}
 
-        self
+impl<'a, H: Transport> Keys<'a, H> {
+    pub(crate) fn new(client: &'a Client<H>) -> Keys<'a, H> {
+        Self {
+            client: Cow::Borrowed(client),
+        }
     }
 
-    pub fn exclude_fields(mut self, fields: String) -> Self {
-        self.filters.exclude_fields.replace(fields);
-
+    /// Overrides the timeouts of the client for requests on keys.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.client = Cow::Owned(self.client.with_timeout(timeout));
         self
     }
 
-    /// Returns generated scoped search key and its expiration time in seconds
-    pub fn build(self) -> Result<(String, u64), Error> {
-        let expires_at = self.filters.expires_at;
-        let params = serde_json::to_string(&self.filters).unwrap();
-
-        let mut mac = Hmac::<Sha256>::new_from_slice(self.key.as_bytes()).unwrap();
-        mac.update(params.as_bytes());
-        let result = mac.finalize();
+    #[instrument]
+    pub async fn create(&self, key: &ApiKey) -> Result<ApiKey, Error> {
+        self.client.post((key, once(PATH))).await
+    }
 
-        let standard = base64::engine::general_purpose::STANDARD;
-        let digest = standard.encode(result.into_bytes());
+    #[instrument]
+    pub async fn retreive(&self, id: &str) -> Result<ApiKeyResponse, Error> {
+        self.client.get([PATH, id].into_iter()).await
+    }
 
-        let key_prefix = &self.key.as_str()[0..4];
-        let raw_scoped_key = format!("{}{}{}", digest, key_prefix, params);
+    #[instrument]
+    pub async fn retreive_all(&self) -> Result<ApiKeyResponses, Error> {
+        self.client.get(once(PATH)).await
+    }
 
-        Ok((standard.encode(raw_scoped_key.as_bytes()), expires_at))
+    #[instrument]
+    pub async fn delete(&self, id: &str) -> Result<ApiKeyDeleteResponse, Error> {
+        self.client.delete([PATH, id].into_iter()).await
     }
 }
 
@@ -148,15 +158,59 @@ pub struct ApiKeyDeleteResponse {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ApiKeyResponses {
-    keys: Vec<ApiKeyResponse>,
+    pub keys: Vec<ApiKeyResponse>,
 }
 
 #[skip_serializing_none]
+#[serde_as]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ApiKeyResponse {
     pub id: usize,
     pub description: String,
-    pub actions: Vec<String>,
+    pub actions: Vec<Action>,
     pub collections: Vec<String>,
     pub value_prefix: String,
+    #[serde_as(as = "Option<TimestampSeconds<i64>>")]
+    pub expires_at: Option<SystemTime>,
+    pub autodelete: Option<bool>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_api_key_serde() {
+        let key = ApiKey::search_only(["products"])
+            .collection_prefix("org_1.")
+            .expires_at(SystemTime::UNIX_EPOCH + Duration::from_secs(1700000000));
+
+        assert_eq!(
+            serde_json::to_value(&key).unwrap(),
+            serde_json::json!({
+                "description": "search only key",
+                "actions": ["documents:search"],
+                "collections": ["products", "org_1\\..*"],
+                "expires_at": 1700000000
+            })
+        );
+
+        let res: ApiKeyResponse = serde_json::from_str(
+            r#"{
+                "actions": ["documents:*", "collections:get"],
+                "autodelete": false,
+                "collections": ["*"],
+                "description": "admin",
+                "expires_at": 64723363199,
+                "id": 1,
+                "value_prefix": "Hu4o"
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(res.actions, [Action::DocumentsAll, Action::CollectionsGet]);
+        assert_eq!(
+            res.expires_at,
+            Some(SystemTime::UNIX_EPOCH + Duration::from_secs(64723363199))
+        );
+    }
 }
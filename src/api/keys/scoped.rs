@@ -0,0 +1,394 @@
+use crate::{api::search::SearchQuery, error::*, Error};
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sha2::Sha256;
+use std::time::{Duration, SystemTime};
+
+// base64 of a sha256 digest
+const DIGEST_LEN: usize = 44;
+const KEY_PREFIX_LEN: usize = 4;
+
+pub fn generate_scoped_search_key(
+    key: impl AsRef<str>,
+    expire_in: Duration,
+) -> GenerateScopedSearchKeyBuilder {
+    GenerateScopedSearchKeyBuilder {
+        key: key.as_ref().to_owned(),
+        ..Default::default()
+    }
+    .expire_in(expire_in)
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct GenerateScopedSearchKeyBuilder {
+    key: String,
+    filters: ScopedSearchFilters,
+}
+
+/// Search parameters embedded in a scoped search key.
+///
+/// Embedded parameters override the ones sent with the search request.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScopedSearchFilters {
+    #[serde(flatten)]
+    pub params: Map<String, Value>,
+    /// `None` if the key expires with its parent key.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<u64>,
+}
+
+impl ScopedSearchFilters {
+    pub fn get(&self, param: &str) -> Option<&Value> {
+        self.params.get(param)
+    }
+}
+
+impl GenerateScopedSearchKeyBuilder {
+    /// Saturates at the furthest representable time, i.e. for `Duration::MAX`.
+    pub fn expire_in(mut self, expire_in: Duration) -> Self {
+        let now = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default();
+        let expire_at = now.saturating_add(expire_in);
+        self.filters.expires_at = Some(expire_at.as_secs());
+
+        self
+    }
+
+    /// Expiration time in seconds since unix epoch.
+    pub fn expires_at(mut self, expires_at: u64) -> Self {
+        self.filters.expires_at = Some(expires_at);
+
+        self
+    }
+
+    /// The key expires with its parent key.
+    pub fn without_expiry(mut self) -> Self {
+        self.filters.expires_at = None;
+
+        self
+    }
+
+    pub fn query_by(self, query_by: impl Into<String>) -> Self {
+        self.param("query_by", query_by.into())
+    }
+
+    /// Accepts a raw expression or a [`Filter`](crate::Filter).
+    pub fn filter_by(self, filter_by: impl Into<String>) -> Self {
+        self.param("filter_by", filter_by.into())
+    }
+
+    /// Accepts a raw expression or a [`Sort`](crate::Sort).
+    pub fn sort_by(self, sort_by: impl Into<String>) -> Self {
+        self.param("sort_by", sort_by.into())
+    }
+
+    pub fn facet_by(self, facet_by: impl Into<String>) -> Self {
+        self.param("facet_by", facet_by.into())
+    }
+
+    pub fn per_page(self, per_page: usize) -> Self {
+        self.param("per_page", per_page)
+    }
+
+    pub fn limit_hits(self, limit_hits: usize) -> Self {
+        self.param("limit_hits", limit_hits)
+    }
+
+    pub fn include_fields(self, fields: impl Into<String>) -> Self {
+        self.param("include_fields", fields.into())
+    }
+
+    pub fn exclude_fields(self, fields: impl Into<String>) -> Self {
+        self.param("exclude_fields", fields.into())
+    }
+
+    /// Embed any search parameter, i.e. `param("prioritize_exact_match", false)`.
+    ///
+    /// `expires_at` sets the expiration time, like [`Self::expires_at`].
+    pub fn param(mut self, name: impl Into<String>, value: impl Into<Value>) -> Self {
+        let (name, value) = (name.into(), value.into());
+
+        // signed once, an invalid expiration fails the build
+        match (name.as_str(), value.as_u64()) {
+            ("expires_at", Some(expires_at)) => self.filters.expires_at = Some(expires_at),
+            _ => {
+                self.filters.params.insert(name, value);
+            }
+        }
+
+        self
+    }
+
+    /// Embed every parameter set on `query`, except for `q`.
+    pub fn search_query(mut self, query: &SearchQuery) -> Self {
+        if let Ok(Value::Object(params)) = serde_json::to_value(query) {
+            for (name, value) in params.into_iter().filter(|(name, _)| name != "q") {
+                self = self.param(name, value);
+            }
+        }
+
+        self
+    }
+
+    /// Returns generated scoped search key and its expiration time in seconds
+    pub fn build(self) -> Result<(String, Option<u64>), Error> {
+        // the prefix is read back by typesense as the first bytes of the decoded key
+        if self.key.chars().count() < KEY_PREFIX_LEN {
+            return Err(Error::ScopedSearchKeyTooShort);
+        }
+        let key_prefix = match self.key.get(..KEY_PREFIX_LEN) {
+            Some(prefix) if prefix.is_ascii() => prefix,
+            _ => {
+                let prefix = self.key.chars().take(KEY_PREFIX_LEN).collect();
+                return Err(Error::NonAsciiKeyPrefix(prefix));
+            }
+        };
+
+        if let Some(expires_at) = self.filters.params.get("expires_at") {
+            return Err(Error::InvalidScopedSearchExpiry(expires_at.clone()));
+        }
+
+        let expires_at = self.filters.expires_at;
+        let params = serde_json::to_string(&self.filters).toss_scoped_search_params_to_json()?;
+
+        let digest = sign(&self.key, &params)?;
+
+        let raw_scoped_key = format!("{}{}{}", digest, key_prefix, params);
+
+        let standard = base64::engine::general_purpose::STANDARD;
+        Ok((standard.encode(raw_scoped_key.as_bytes()), expires_at))
+    }
+}
+
+/// Scoped search key decoded with [`decode_scoped_search_key`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScopedSearchKey {
+    /// base64 encoded HMAC-SHA256 of `raw_params` signed by the parent key
+    pub digest: String,
+    /// first characters of the parent key
+    pub key_prefix: String,
+    pub params: ScopedSearchFilters,
+    /// embedded parameters exactly as they were signed
+    pub raw_params: String,
+}
+
+impl ScopedSearchKey {
+    /// Whether this key was generated from `parent_key`.
+    pub fn verify(&self, parent_key: &str) -> bool {
+        if !parent_key.starts_with(&self.key_prefix) {
+            return false;
+        }
+
+        let standard = base64::engine::general_purpose::STANDARD;
+        let Ok(digest) = standard.decode(&self.digest) else {
+            return false;
+        };
+
+        let Ok(mut mac) = Hmac::<Sha256>::new_from_slice(parent_key.as_bytes()) else {
+            return false;
+        };
+        mac.update(self.raw_params.as_bytes());
+        mac.verify_slice(&digest).is_ok()
+    }
+}
+
+/// Parse a scoped search key back into its digest, parent key prefix and embedded parameters.
+///
+/// The key is not verified, use [`ScopedSearchKey::verify`] with the parent key.
+pub fn decode_scoped_search_key(scoped_key: impl AsRef<str>) -> Result<ScopedSearchKey, Error> {
+    let standard = base64::engine::general_purpose::STANDARD;
+    let raw = standard
+        .decode(scoped_key.as_ref())
+        .ok()
+        .and_then(|raw| String::from_utf8(raw).ok())
+        .ok_or(Error::InvalidScopedSearchKey("not base64 encoded utf-8"))?;
+
+    let (Some(digest), Some(key_prefix), Some(raw_params)) = (
+        raw.get(..DIGEST_LEN),
+        raw.get(DIGEST_LEN..DIGEST_LEN + KEY_PREFIX_LEN),
+        raw.get(DIGEST_LEN + KEY_PREFIX_LEN..),
+    ) else {
+        return Err(Error::InvalidScopedSearchKey("too short"));
+    };
+
+    let params =
+        serde_json::from_str(raw_params).toss_deserialize_text_with(|| raw_params.to_owned())?;
+
+    Ok(ScopedSearchKey {
+        digest: digest.to_owned(),
+        key_prefix: key_prefix.to_owned(),
+        params,
+        raw_params: raw_params.to_owned(),
+    })
+}
+
+fn sign(key: &str, params: &str) -> Result<String, Error> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key.as_bytes()).toss_invalid_hmac_key()?;
+    mac.update(params.as_bytes());
+    let result = mac.finalize();
+
+    let standard = base64::engine::general_purpose::STANDARD;
+    Ok(standard.encode(result.into_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Filter, Sort};
+
+    #[test]
+    fn test_scoped_search_key_roundtrip() {
+        let parent_key = "RN23GFr1s6jQ9kgSNg2O7fYcAUXU7127";
+        let (key, expires_at) = generate_scoped_search_key(parent_key, Duration::ZERO)
+            .expires_at(1906054106)
+            .filter_by(Filter::raw("company_id:124"))
+            .sort_by(Sort::desc("created_at"))
+            .per_page(10)
+            .search_query(&SearchQuery::new("shoes").query_by(["title"]))
+            .build()
+            .unwrap();
+        assert_eq!(expires_at, Some(1906054106));
+
+        let decoded = decode_scoped_search_key(&key).unwrap();
+        assert_eq!(decoded.key_prefix, "RN23");
+        assert_eq!(decoded.params.expires_at, Some(1906054106));
+        assert_eq!(decoded.params.get("per_page"), Some(&Value::from(10)));
+        assert_eq!(decoded.params.get("query_by"), Some(&Value::from("title")));
+        assert_eq!(decoded.params.get("q"), None);
+        assert_eq!(
+            decoded.params.get("sort_by"),
+            Some(&Value::from("created_at:desc"))
+        );
+
+        assert!(decoded.verify(parent_key));
+        assert!(!decoded.verify("RN23GFr1s6jQ9kgSNg2O7fYcAUXU7128"));
+        assert!(!decoded.verify("other"));
+
+        assert!(decode_scoped_search_key("not a key").is_err());
+    }
+
+    // vectors from the official typesense clients
+    #[test]
+    fn test_scoped_search_key_reference() {
+        // typesense-js, typesense-php, typesense-ruby and typesense-go
+        let (key, expires_at) =
+            generate_scoped_search_key("RN23GFr1s6jQ9kgSNg2O7fYcAUXU7127", Duration::ZERO)
+                .without_expiry()
+                .filter_by("company_id:124")
+                .build()
+                .unwrap();
+        assert_eq!(expires_at, None);
+        assert_eq!(
+            key,
+            "SC9sT0hncHFwTHNFc3U3d3psRDZBUGNXQUViQUdDNmRHSmJFQnNnczJ4VT1STjIzeyJmaWx0ZXJfYnkiOiJjb21wYW55X2lkOjEyNCJ9"
+        );
+
+        // with an expiration, signed after the other parameters
+        let (key, _) =
+            generate_scoped_search_key("RN23GFr1s6jQ9kgSNg2O7fYcAUXU7127", Duration::ZERO)
+                .expires_at(1906054106)
+                .filter_by("company_id:124")
+                .build()
+                .unwrap();
+        assert_eq!(
+            key,
+            "OW9DYWZGS1Q1RGdSbmo0S1QrOWxhbk9PL2kxbTU1eXA3bCthdmE5eXJKRT1STjIzeyJmaWx0ZXJfYnkiOiJjb21wYW55X2lkOjEyNCIsImV4cGlyZXNfYXQiOjE5MDYwNTQxMDZ9"
+        );
+
+        let decoded = decode_scoped_search_key(&key).unwrap();
+        assert_eq!(
+            decoded.raw_params,
+            r#"{"filter_by":"company_id:124","expires_at":1906054106}"#
+        );
+        assert!(decoded.verify("RN23GFr1s6jQ9kgSNg2O7fYcAUXU7127"));
+    }
+
+    // keys without an expiration expire with their parent key
+    #[test]
+    fn test_decode_scoped_search_key_without_expiry() {
+        let decoded = decode_scoped_search_key(
+            "SC9sT0hncHFwTHNFc3U3d3psRDZBUGNXQUViQUdDNmRHSmJFQnNnczJ4VT1STjIzeyJmaWx0ZXJfYnkiOiJjb21wYW55X2lkOjEyNCJ9",
+        )
+        .unwrap();
+        assert_eq!(decoded.params.expires_at, None);
+        assert_eq!(
+            decoded.params.get("filter_by"),
+            Some(&Value::from("company_id:124"))
+        );
+        assert!(decoded.verify("RN23GFr1s6jQ9kgSNg2O7fYcAUXU7127"));
+    }
+
+    #[test]
+    fn test_scoped_search_key_expires_at_param() {
+        let parent_key = "RN23GFr1s6jQ9kgSNg2O7fYcAUXU7127";
+        let (key, expires_at) = generate_scoped_search_key(parent_key, Duration::ZERO)
+            .param("expires_at", 1906054106)
+            .build()
+            .unwrap();
+        assert_eq!(expires_at, Some(1906054106));
+
+        let decoded = decode_scoped_search_key(key).unwrap();
+        assert_eq!(decoded.raw_params, r#"{"expires_at":1906054106}"#);
+
+        let result = generate_scoped_search_key(parent_key, Duration::ZERO)
+            .param("expires_at", "tomorrow")
+            .build();
+        assert!(matches!(result, Err(Error::InvalidScopedSearchExpiry(v)) if v == "tomorrow"));
+    }
+
+    #[test]
+    fn test_scoped_search_key_errors() {
+        let build = |key: &str| generate_scoped_search_key(key, Duration::ZERO).build();
+
+        assert!(matches!(build(""), Err(Error::ScopedSearchKeyTooShort)));
+        assert!(matches!(build("abc"), Err(Error::ScopedSearchKeyTooShort)));
+        assert!(matches!(build("abcé"), Err(Error::NonAsciiKeyPrefix(p)) if p == "abcé"));
+        assert!(matches!(build("éabcd"), Err(Error::NonAsciiKeyPrefix(p)) if p == "éabc"));
+
+        let (_, expires_at) = generate_scoped_search_key("abcd", Duration::MAX)
+            .build()
+            .unwrap();
+        assert_eq!(expires_at, Some(u64::MAX));
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn prop_scoped_search_key_never_panics(key in ".*", filter_by in ".*", expire_in: u64) {
+            let _ = generate_scoped_search_key(&key, Duration::from_secs(expire_in))
+                .filter_by(filter_by)
+                .build();
+        }
+
+        #[test]
+        fn prop_scoped_search_key_roundtrip(
+            key in "[ -~]{4,64}",
+            other in "[ -~]{4,64}",
+            filter_by in ".*",
+            expires_at: u64,
+            per_page: usize,
+        ) {
+            let (scoped, _) = generate_scoped_search_key(&key, Duration::ZERO)
+                .expires_at(expires_at)
+                .filter_by(filter_by.clone())
+                .per_page(per_page)
+                .build()
+                .unwrap();
+
+            let decoded = decode_scoped_search_key(&scoped).unwrap();
+            proptest::prop_assert_eq!(&decoded.key_prefix, &key[..KEY_PREFIX_LEN]);
+            proptest::prop_assert_eq!(decoded.params.expires_at, Some(expires_at));
+            proptest::prop_assert_eq!(decoded.params.get("filter_by"), Some(&Value::from(filter_by)));
+            proptest::prop_assert_eq!(decoded.params.get("per_page"), Some(&Value::from(per_page)));
+            proptest::prop_assert!(decoded.verify(&key));
+            proptest::prop_assert_eq!(decoded.verify(&other), key == other);
+        }
+
+        #[test]
+        fn prop_decode_never_panics(scoped in ".*") {
+            let _ = decode_scoped_search_key(scoped);
+        }
+    }
+}
@@ -1,19 +1,45 @@
-use crate::schema::OwnedField;
+use crate::schema::{OwnedField, VoiceQueryModel};
 use serde::{de::Visitor, Deserialize, Serialize};
 use serde_with::skip_serializing_none;
+use std::collections::HashMap;
 
 pub mod alias;
 pub mod collection;
 pub mod documents;
 pub mod keys;
+pub mod search;
 
 #[skip_serializing_none]
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct CollectionResponse {
     pub name: String,
+    #[serde(default)]
     pub num_documents: usize,
     pub fields: Vec<OwnedField>,
-    pub default_sorting_field: String,
+    /// typesense returns an empty string when there is no default sorting field
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub default_sorting_field: Option<String>,
+    /// creation time in seconds since unix epoch
+    #[serde(default)]
+    pub created_at: u64,
+    #[serde(default)]
+    pub enable_nested_fields: bool,
+    pub symbols_to_index: Option<Vec<String>>,
+    pub token_separators: Option<Vec<String>>,
+    pub num_memory_shards: Option<usize>,
+    pub metadata: Option<serde_json::Value>,
+    pub voice_query_model: Option<VoiceQueryModel>,
+    /// fields of the response that are not modeled above
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+fn empty_string_as_none<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let s = Option::<String>::deserialize(deserializer)?;
+    Ok(s.filter(|s| !s.is_empty()))
 }
 
 #[skip_serializing_none]
@@ -32,16 +58,17 @@ pub struct ImportResponse {
 
 #[skip_serializing_none]
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct MultiSearchResponse<T> {
-    pub results: Vec<SearchResponse<T>>,
+pub struct MultiSearchResponse<T, J = ()> {
+    pub results: Vec<SearchResponse<T, J>>,
 }
 
 #[skip_serializing_none]
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct SearchResponse<T> {
-    pub facet_counts: Vec<usize>,
+pub struct SearchResponse<T, J = ()> {
+    #[serde(default)]
+    pub facet_counts: Vec<FacetCounts>,
     pub found: usize,
-    pub hits: Vec<SearchHit<T>>,
+    pub hits: Vec<SearchHit<T, J>>,
     pub out_of: usize,
     pub page: usize,
     pub request_params: SearchParams,
@@ -49,12 +76,126 @@ pub struct SearchResponse<T> {
     pub search_time_ms: usize,
 }
 
-#[skip_serializing_none]
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct SearchHit<T> {
+/// A search hit with its `document`.
+///
+/// Documents of other collections included through joins (`include_fields: $customers(name)`)
+/// are nested in the document under the collection name, and are deserialized into `joined`.
+/// i.e. `J` would be `struct OrderJoins { customers: Customer }`, and are serialized back
+/// into the document the same way.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(from = "RawSearchHit<T, J>")]
+pub struct SearchHit<T, J = ()> {
     pub document: T,
+    pub joined: J,
     pub highlights: Vec<SearchHighlight>,
     pub text_match: usize,
+    /// distance from the query vector; set for vector and hybrid searches
+    pub vector_distance: Option<f32>,
+    pub hybrid_search_info: Option<HybridSearchInfo>,
+    /// distance in meters of each geopoint field from the point given in `sort_by`
+    pub geo_distance_meters: Option<HashMap<String, f64>>,
+}
+
+#[derive(Deserialize)]
+struct RawSearchHit<T, J> {
+    document: JoinedDocument<T, J>,
+    #[serde(default)]
+    highlights: Vec<SearchHighlight>,
+    #[serde(default)]
+    text_match: usize,
+    vector_distance: Option<f32>,
+    hybrid_search_info: Option<HybridSearchInfo>,
+    geo_distance_meters: Option<HashMap<String, f64>>,
+}
+
+// a hit borrowed back into the shape typesense sends it in
+#[skip_serializing_none]
+#[derive(Serialize)]
+struct RawSearchHitRef<'a, T, J> {
+    document: JoinedDocument<&'a T, &'a J>,
+    highlights: &'a [SearchHighlight],
+    text_match: usize,
+    vector_distance: Option<f32>,
+    hybrid_search_info: Option<&'a HybridSearchInfo>,
+    geo_distance_meters: Option<&'a HashMap<String, f64>>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct JoinedDocument<T, J> {
+    #[serde(flatten)]
+    document: T,
+    #[serde(flatten)]
+    joined: J,
+}
+
+impl<T, J> From<RawSearchHit<T, J>> for SearchHit<T, J> {
+    fn from(raw: RawSearchHit<T, J>) -> Self {
+        let RawSearchHit {
+            document: JoinedDocument { document, joined },
+            highlights,
+            text_match,
+            vector_distance,
+            hybrid_search_info,
+            geo_distance_meters,
+        } = raw;
+
+        Self {
+            document,
+            joined,
+            highlights,
+            text_match,
+            vector_distance,
+            hybrid_search_info,
+            geo_distance_meters,
+        }
+    }
+}
+
+impl<T: Serialize, J: Serialize> Serialize for SearchHit<T, J> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        RawSearchHitRef {
+            document: JoinedDocument {
+                document: &self.document,
+                joined: &self.joined,
+            },
+            highlights: &self.highlights,
+            text_match: self.text_match,
+            vector_distance: self.vector_distance,
+            hybrid_search_info: self.hybrid_search_info.as_ref(),
+            geo_distance_meters: self.geo_distance_meters.as_ref(),
+        }
+        .serialize(serializer)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct HybridSearchInfo {
+    pub rank_fusion_score: f32,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FacetCounts {
+    pub field_name: String,
+    pub counts: Vec<FacetCount>,
+    pub stats: Option<FacetStats>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FacetCount {
+    pub count: usize,
+    pub highlighted: String,
+    pub value: String,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FacetStats {
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    pub avg: Option<f64>,
+    pub sum: Option<f64>,
+    pub total_values: Option<usize>,
 }
 
 #[skip_serializing_none]
@@ -174,7 +315,7 @@ impl<'de> Visitor<'de> for MatchedTokensVisitor {
         let mut ret = seq
             .size_hint()
             .map(|n| if n == 0 { 1 } else { n })
-            .map(|n| Vec::with_capacity(n))
+            .map(Vec::with_capacity)
             .unwrap_or_default();
 
         while let Some(item) = seq.next_element()? {
@@ -184,3 +325,65 @@ impl<'de> Visitor<'de> for MatchedTokensVisitor {
         Ok(Self::Value::Tokens(ret))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collection_response_serde() {
+        let json = r#"{
+            "created_at": 1716465062,
+            "default_sorting_field": "",
+            "enable_nested_fields": false,
+            "fields": [
+                {
+                    "facet": false,
+                    "index": true,
+                    "infix": false,
+                    "locale": "",
+                    "name": "title",
+                    "optional": false,
+                    "sort": false,
+                    "stem": false,
+                    "store": true,
+                    "type": "string"
+                },
+                {
+                    "facet": false,
+                    "hnsw_params": {"M": 16, "ef_construction": 200},
+                    "index": true,
+                    "name": "embedding",
+                    "num_dim": 384,
+                    "optional": false,
+                    "type": "float[]",
+                    "vec_dist": "cosine"
+                }
+            ],
+            "name": "products",
+            "num_documents": 42,
+            "num_memory_shards": 4,
+            "symbols_to_index": [],
+            "token_separators": ["-"],
+            "synonym_sets": []
+        }"#;
+
+        let res: CollectionResponse = serde_json::from_str(json).unwrap();
+        assert_eq!(res.name, "products");
+        assert_eq!(res.default_sorting_field, None);
+        assert_eq!(res.created_at, 1716465062);
+        assert_eq!(res.num_memory_shards, Some(4));
+        assert_eq!(res.fields[0].store, Some(true));
+        assert_eq!(res.fields[1].num_dim, Some(384));
+        assert_eq!(res.extra["synonym_sets"], serde_json::json!([]));
+
+        let roundtrip: CollectionResponse =
+            serde_json::from_str(&serde_json::to_string(&res).unwrap()).unwrap();
+        assert_eq!(roundtrip, res);
+
+        let res: CollectionResponse =
+            serde_json::from_str(r#"{"name": "a", "fields": [], "default_sorting_field": "b"}"#)
+                .unwrap();
+        assert_eq!(res.default_sorting_field.as_deref(), Some("b"));
+    }
+}
@@ -0,0 +1,351 @@
+use crate::{
+    geo::{Distance, GeoPoint},
+    timestamp::{FilterTime, Precision},
+    EnumField,
+};
+use std::{fmt, marker::PhantomData};
+
+/// Name of a document field holding a `T`, i.e. `Order::fields().status` from the derive.
+///
+/// Optional fields hold their inner type.
+pub struct FieldRef<T> {
+    name: &'static str,
+    ty: PhantomData<fn() -> T>,
+}
+
+impl<T> FieldRef<T> {
+    pub const fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            ty: PhantomData,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+impl<T> Clone for FieldRef<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for FieldRef<T> {}
+
+impl<T> fmt::Debug for FieldRef<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("FieldRef").field(&self.name).finish()
+    }
+}
+
+impl<T> fmt::Display for FieldRef<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name)
+    }
+}
+
+// arrays of enums match any of their values
+impl<E: EnumField> From<FieldRef<Vec<E>>> for FieldRef<E> {
+    fn from(field: FieldRef<Vec<E>>) -> Self {
+        Self::new(field.name)
+    }
+}
+
+/// A `filter_by` expression.
+///
+/// Filters can be combined with [`Filter::and`] and [`Filter::or`], and are
+/// passed to [`SearchQuery::filter_by`](super::SearchQuery::filter_by).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Filter {
+    expr: String,
+    // whether the expression is joined with `&&` or `||` at the top level
+    compound: bool,
+}
+
+impl Filter {
+    /// Filter from a raw typesense filter expression, i.e. `num_employees:>100`.
+    pub fn raw(expr: impl Into<String>) -> Self {
+        let expr = expr.into();
+        let compound = expr.contains("&&") || expr.contains("||");
+
+        Self { expr, compound }
+    }
+
+    /// Documents whose enum `field` is `value`, i.e. ``status:=`shipped` ``.
+    ///
+    /// `field` must hold `E`, or an array of it, i.e. `Filter::variant(Order::fields().status, ..)`.
+    pub fn variant<E: EnumField>(field: impl Into<FieldRef<E>>, value: E) -> Self {
+        let field = field.into();
+        Self::single(format!("{field}:=`{}`", value.as_str()))
+    }
+
+    /// Documents whose enum `field` is any of `values`, i.e. ``status:=[`pending`,`shipped`]``.
+    pub fn variants<E: EnumField>(
+        field: impl Into<FieldRef<E>>,
+        values: impl IntoIterator<Item = E>,
+    ) -> Self {
+        let field = field.into();
+        let values = values
+            .into_iter()
+            .map(|v| format!("`{}`", v.as_str()))
+            .collect::<Vec<_>>()
+            .join(",");
+
+        Self::single(format!("{field}:=[{values}]"))
+    }
+
+    /// Documents whose timestamp `field` is before `time`, i.e. `published_at:<1700000000`.
+    ///
+    /// `P` is the precision `field` is stored in, inferred from [`Timestamp`](crate::Timestamp)s
+    /// and spelled out for other dates, i.e. `Filter::before::<Millis>("edited_at", time)`.
+    pub fn before<P: Precision>(field: &str, time: impl FilterTime<P>) -> Self {
+        Self::single(format!("{field}:<{}", time.filter_value()))
+    }
+
+    /// Documents whose timestamp `field` is after `time`, see [`Filter::before`].
+    pub fn after<P: Precision>(field: &str, time: impl FilterTime<P>) -> Self {
+        Self::single(format!("{field}:>{}", time.filter_value()))
+    }
+
+    /// Documents whose timestamp `field` is between `start` and `end`, both inclusive, see
+    /// [`Filter::before`].
+    pub fn between<P: Precision>(
+        field: &str,
+        start: impl FilterTime<P>,
+        end: impl FilterTime<P>,
+    ) -> Self {
+        Self::single(format!(
+            "{field}:[{}..{}]",
+            start.filter_value(),
+            end.filter_value()
+        ))
+    }
+
+    /// Documents whose geopoint `field` is within `radius` of `center`.
+    pub fn geo_radius(field: &str, center: GeoPoint, radius: Distance) -> Self {
+        Self::single(format!("{field}:({center}, {radius})"))
+    }
+
+    /// Documents whose geopoint `field` is inside the polygon made of `points`.
+    pub fn geo_polygon(field: &str, points: impl IntoIterator<Item = GeoPoint>) -> Self {
+        let points = points
+            .into_iter()
+            .map(|p| p.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        Self::single(format!("{field}:({points})"))
+    }
+
+    /// Filter on the fields of documents joined from `collection`, i.e. `$customers(country:=US)`.
+    pub fn join(collection: &str, filter: Filter) -> Self {
+        Self::single(format!("${collection}({filter})"))
+    }
+
+    pub fn and(self, other: Filter) -> Self {
+        Self {
+            expr: format!("{} && {}", self.group(), other.group()),
+            compound: true,
+        }
+    }
+
+    pub fn or(self, other: Filter) -> Self {
+        Self {
+            expr: format!("{} || {}", self.group(), other.group()),
+            compound: true,
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.expr
+    }
+
+    fn single(expr: String) -> Self {
+        Self {
+            expr,
+            compound: false,
+        }
+    }
+
+    fn group(self) -> String {
+        if self.compound {
+            format!("({})", self.expr)
+        } else {
+            self.expr
+        }
+    }
+}
+
+impl fmt::Display for Filter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.expr)
+    }
+}
+
+impl From<Filter> for String {
+    fn from(filter: Filter) -> Self {
+        filter.expr
+    }
+}
+
+/// A `sort_by` expression on a single field.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sort {
+    field: String,
+    point: Option<GeoPoint>,
+    exclude_radius: Option<Distance>,
+    precision: Option<Distance>,
+    desc: bool,
+}
+
+impl Sort {
+    pub fn asc(field: impl Into<String>) -> Self {
+        Self {
+            field: field.into(),
+            point: None,
+            exclude_radius: None,
+            precision: None,
+            desc: false,
+        }
+    }
+
+    pub fn desc(field: impl Into<String>) -> Self {
+        Self {
+            desc: true,
+            ..Self::asc(field)
+        }
+    }
+
+    /// Sort by distance of the geopoint `field` from `point`, closest first.
+    pub fn geo(field: impl Into<String>, point: GeoPoint) -> Self {
+        Self {
+            point: Some(point),
+            ..Self::asc(field)
+        }
+    }
+
+    /// Reverse the sort order.
+    pub fn reverse(mut self) -> Self {
+        self.desc = !self.desc;
+        self
+    }
+
+    /// Treat all documents within `radius` of the point as equally distant.
+    pub fn exclude_radius(mut self, radius: Distance) -> Self {
+        self.exclude_radius.replace(radius);
+        self
+    }
+
+    /// Bucket distances into groups of `precision`.
+    pub fn precision(mut self, precision: Distance) -> Self {
+        self.precision.replace(precision);
+        self
+    }
+}
+
+impl fmt::Display for Sort {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.field)?;
+
+        if let Some(point) = &self.point {
+            write!(f, "({point}")?;
+
+            if let Some(radius) = &self.exclude_radius {
+                write!(f, ", exclude_radius: {}{}", radius.value(), radius.unit())?;
+            }
+            if let Some(precision) = &self.precision {
+                write!(f, ", precision: {}{}", precision.value(), precision.unit())?;
+            }
+
+            f.write_str(")")?;
+        }
+
+        f.write_str(if self.desc { ":desc" } else { ":asc" })
+    }
+}
+
+impl From<Sort> for String {
+    fn from(sort: Sort) -> Self {
+        sort.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::timestamp::{Millis, Seconds, Timestamp};
+    use std::time::{Duration, SystemTime};
+
+    #[test]
+    fn test_geo_filter() {
+        let filter = Filter::geo_radius("location", GeoPoint::new(48.9, 2.34), Distance::Km(5.1));
+        assert_eq!(filter.as_str(), "location:(48.9, 2.34, 5.1 km)");
+
+        let filter = Filter::geo_polygon(
+            "location",
+            [
+                GeoPoint::new(48.86, 2.32),
+                GeoPoint::new(48.85, 2.32),
+                GeoPoint::new(48.85, 2.33),
+            ],
+        )
+        .and(Filter::raw("category:shoes"));
+        assert_eq!(
+            filter.as_str(),
+            "location:(48.86, 2.32, 48.85, 2.32, 48.85, 2.33) && category:shoes"
+        );
+    }
+
+    #[test]
+    fn test_time_filter() {
+        let time = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+
+        let filter = Filter::before::<Seconds>("published_at", time);
+        assert_eq!(filter.as_str(), "published_at:<1700000000");
+
+        let filter = Filter::before::<Millis>("edited_at", time + Duration::from_millis(250));
+        assert_eq!(filter.as_str(), "edited_at:<1700000000250");
+
+        let filter = Filter::after("edited_at", Timestamp::<Millis>::from(time));
+        assert_eq!(filter.as_str(), "edited_at:>1700000000000");
+
+        let filter = Filter::between(
+            "published_at",
+            time - Duration::from_secs(60),
+            Timestamp::<Seconds>::from_epoch(1_700_000_000),
+        );
+        assert_eq!(filter.as_str(), "published_at:[1699999940..1700000000]");
+    }
+
+    #[test]
+    fn test_join_filter() {
+        let filter = Filter::raw("amount:>100").and(Filter::join(
+            "customers",
+            Filter::raw("country:=US").or(Filter::raw("country:=CA")),
+        ));
+        assert_eq!(
+            filter.as_str(),
+            "amount:>100 && $customers(country:=US || country:=CA)"
+        );
+    }
+
+    #[test]
+    fn test_geo_sort() {
+        let sort =
+            Sort::geo("location", GeoPoint::new(48.853, 2.344)).exclude_radius(Distance::Mi(2.0));
+        assert_eq!(
+            sort.to_string(),
+            "location(48.853, 2.344, exclude_radius: 2mi):asc"
+        );
+
+        let sort = Sort::geo("location", GeoPoint::new(48.853, 2.344))
+            .precision(Distance::Km(1.5))
+            .reverse();
+        assert_eq!(
+            sort.to_string(),
+            "location(48.853, 2.344, precision: 1.5km):desc"
+        );
+    }
+}
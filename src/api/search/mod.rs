@@ -0,0 +1,406 @@
+use serde::{Serialize, Serializer};
+use serde_with::skip_serializing_none;
+use std::fmt::{self, Write};
+
+mod filter;
+pub use filter::*;
+
+/// Search parameters sent to `GET /collections/:collection/documents/search`
+/// or as one of the searches in `POST /multi_search`.
+#[skip_serializing_none]
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct SearchQuery {
+    q: String,
+    query_by: Option<String>,
+    query_by_weights: Option<String>,
+    prefix: Option<bool>,
+    filter_by: Option<String>,
+    sort_by: Option<String>,
+    facet_by: Option<String>,
+    max_facet_values: Option<usize>,
+    page: Option<usize>,
+    per_page: Option<usize>,
+    offset: Option<usize>,
+    limit: Option<usize>,
+    include_fields: Option<String>,
+    exclude_fields: Option<String>,
+    highlight_fields: Option<String>,
+    num_typos: Option<String>,
+    limit_hits: Option<usize>,
+    exhaustive_search: Option<bool>,
+    search_cutoff_ms: Option<usize>,
+    use_cache: Option<bool>,
+    cache_ttl: Option<usize>,
+    vector_query: Option<VectorQuery>,
+}
+
+impl SearchQuery {
+    pub fn new(q: impl Into<String>) -> Self {
+        Self {
+            q: q.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Fields to search in. Text and embedding fields can be mixed for hybrid search,
+    /// i.e. `query_by(["title", "embedding"])`.
+    pub fn query_by<T: AsRef<str>>(mut self, fields: impl IntoIterator<Item = T>) -> Self {
+        self.query_by.replace(join(fields));
+        self
+    }
+
+    pub fn query_by_weights<T: ToString>(mut self, weights: impl IntoIterator<Item = T>) -> Self {
+        self.query_by_weights
+            .replace(join(weights.into_iter().map(|w| w.to_string())));
+        self
+    }
+
+    pub fn prefix(mut self, prefix: bool) -> Self {
+        self.prefix.replace(prefix);
+        self
+    }
+
+    pub fn filter_by(mut self, filter_by: impl Into<String>) -> Self {
+        self.filter_by.replace(filter_by.into());
+        self
+    }
+
+    pub fn sort_by(mut self, sort_by: impl Into<String>) -> Self {
+        self.sort_by.replace(sort_by.into());
+        self
+    }
+
+    pub fn facet_by<T: AsRef<str>>(mut self, fields: impl IntoIterator<Item = T>) -> Self {
+        self.facet_by.replace(join(fields));
+        self
+    }
+
+    pub fn max_facet_values(mut self, max_facet_values: usize) -> Self {
+        self.max_facet_values.replace(max_facet_values);
+        self
+    }
+
+    pub fn page(mut self, page: usize) -> Self {
+        self.page.replace(page);
+        self
+    }
+
+    pub fn per_page(mut self, per_page: usize) -> Self {
+        self.per_page.replace(per_page);
+        self
+    }
+
+    pub fn offset(mut self, offset: usize) -> Self {
+        self.offset.replace(offset);
+        self
+    }
+
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit.replace(limit);
+        self
+    }
+
+    pub fn include_fields<T: AsRef<str>>(mut self, fields: impl IntoIterator<Item = T>) -> Self {
+        self.include_fields.replace(join(fields));
+        self
+    }
+
+    /// Include fields of documents joined from `collection`, i.e. `$customers(name, email)`.
+    ///
+    /// Joined documents are nested under the collection name in each hit's document.
+    pub fn include_joined_fields<T: AsRef<str>>(
+        mut self,
+        collection: &str,
+        fields: impl IntoIterator<Item = T>,
+    ) -> Self {
+        let joined = format!("${collection}({})", join(fields));
+
+        match self.include_fields.as_mut() {
+            Some(include_fields) => {
+                include_fields.push(',');
+                include_fields.push_str(&joined);
+            }
+            None => {
+                self.include_fields.replace(joined);
+            }
+        }
+
+        self
+    }
+
+    pub fn exclude_fields<T: AsRef<str>>(mut self, fields: impl IntoIterator<Item = T>) -> Self {
+        self.exclude_fields.replace(join(fields));
+        self
+    }
+
+    pub fn highlight_fields<T: AsRef<str>>(mut self, fields: impl IntoIterator<Item = T>) -> Self {
+        self.highlight_fields.replace(join(fields));
+        self
+    }
+
+    pub fn num_typos(mut self, num_typos: impl Into<String>) -> Self {
+        self.num_typos.replace(num_typos.into());
+        self
+    }
+
+    pub fn limit_hits(mut self, limit_hits: usize) -> Self {
+        self.limit_hits.replace(limit_hits);
+        self
+    }
+
+    pub fn exhaustive_search(mut self, exhaustive_search: bool) -> Self {
+        self.exhaustive_search.replace(exhaustive_search);
+        self
+    }
+
+    pub fn search_cutoff_ms(mut self, search_cutoff_ms: usize) -> Self {
+        self.search_cutoff_ms.replace(search_cutoff_ms);
+        self
+    }
+
+    pub fn use_cache(mut self, use_cache: bool) -> Self {
+        self.use_cache.replace(use_cache);
+        self
+    }
+
+    pub fn cache_ttl(mut self, cache_ttl: usize) -> Self {
+        self.cache_ttl.replace(cache_ttl);
+        self
+    }
+
+    pub fn vector_query(mut self, vector_query: VectorQuery) -> Self {
+        self.vector_query.replace(vector_query);
+        self
+    }
+}
+
+/// Nearest neighbor query on a vector field, rendered as
+/// `field:([0.1, 0.2], k:100, distance_threshold:0.3)`.
+///
+/// Large vectors can exceed the url length limit of `GET` searches,
+/// so prefer `Documents::multi_search` when querying with raw vectors.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VectorQuery {
+    field: String,
+    vector: Vec<f32>,
+    id: Option<String>,
+    k: Option<usize>,
+    distance_threshold: Option<f32>,
+    alpha: Option<f32>,
+    ef: Option<usize>,
+    flat_search_cutoff: Option<usize>,
+}
+
+impl VectorQuery {
+    /// Query `field` with the vector embedded from `q`; used for semantic and hybrid
+    /// search on auto-embedding fields.
+    pub fn new(field: impl Into<String>) -> Self {
+        Self {
+            field: field.into(),
+            vector: Vec::new(),
+            id: None,
+            k: None,
+            distance_threshold: None,
+            alpha: None,
+            ef: None,
+            flat_search_cutoff: None,
+        }
+    }
+
+    pub fn vector(mut self, vector: impl Into<Vec<f32>>) -> Self {
+        self.vector = vector.into();
+        self
+    }
+
+    /// Use the vector of an already indexed document as the query vector.
+    pub fn id(mut self, id: impl Into<String>) -> Self {
+        self.id.replace(id.into());
+        self
+    }
+
+    pub fn k(mut self, k: usize) -> Self {
+        self.k.replace(k);
+        self
+    }
+
+    pub fn distance_threshold(mut self, distance_threshold: f32) -> Self {
+        self.distance_threshold.replace(distance_threshold);
+        self
+    }
+
+    /// Weight given to vector search over keyword search in a hybrid search.
+    pub fn alpha(mut self, alpha: f32) -> Self {
+        self.alpha.replace(alpha);
+        self
+    }
+
+    pub fn ef(mut self, ef: usize) -> Self {
+        self.ef.replace(ef);
+        self
+    }
+
+    pub fn flat_search_cutoff(mut self, flat_search_cutoff: usize) -> Self {
+        self.flat_search_cutoff.replace(flat_search_cutoff);
+        self
+    }
+}
+
+impl fmt::Display for VectorQuery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:([", self.field)?;
+
+        for (i, v) in self.vector.iter().enumerate() {
+            if i > 0 {
+                f.write_char(',')?;
+            }
+            write!(f, "{v}")?;
+        }
+
+        f.write_char(']')?;
+
+        if let Some(id) = &self.id {
+            write!(f, ", id:{id}")?;
+        }
+        if let Some(k) = self.k {
+            write!(f, ", k:{k}")?;
+        }
+        if let Some(distance_threshold) = self.distance_threshold {
+            write!(f, ", distance_threshold:{distance_threshold}")?;
+        }
+        if let Some(alpha) = self.alpha {
+            write!(f, ", alpha:{alpha}")?;
+        }
+        if let Some(ef) = self.ef {
+            write!(f, ", ef:{ef}")?;
+        }
+        if let Some(flat_search_cutoff) = self.flat_search_cutoff {
+            write!(f, ", flat_search_cutoff:{flat_search_cutoff}")?;
+        }
+
+        f.write_char(')')
+    }
+}
+
+impl Serialize for VectorQuery {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct MultiSearchRequest<'a> {
+    pub searches: Vec<CollectionSearch<'a>>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct CollectionSearch<'a> {
+    pub collection: &'a str,
+    #[serde(flatten)]
+    pub query: &'a SearchQuery,
+}
+
+fn join<T: AsRef<str>>(items: impl IntoIterator<Item = T>) -> String {
+    let mut ret = String::new();
+
+    for (i, item) in items.into_iter().enumerate() {
+        if i > 0 {
+            ret.push(',');
+        }
+        ret.push_str(item.as_ref());
+    }
+
+    ret
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::SearchHit;
+
+    #[test]
+    fn test_vector_query_display() {
+        let query = VectorQuery::new("embedding")
+            .vector([0.1, -0.25, 3.0])
+            .k(100)
+            .distance_threshold(0.3);
+        assert_eq!(
+            query.to_string(),
+            "embedding:([0.1,-0.25,3], k:100, distance_threshold:0.3)"
+        );
+
+        let query = VectorQuery::new("embedding").id("doc1").alpha(0.8);
+        assert_eq!(query.to_string(), "embedding:([], id:doc1, alpha:0.8)");
+    }
+
+    #[test]
+    fn test_hybrid_search_hit() {
+        let hit: SearchHit<serde_json::Value> = serde_json::from_str(
+            r#"{
+                "document": {"title": "shoe"},
+                "highlights": [],
+                "text_match": 578730123365187705,
+                "vector_distance": 0.19,
+                "hybrid_search_info": {"rank_fusion_score": 0.8}
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(hit.vector_distance, Some(0.19));
+        assert_eq!(hit.hybrid_search_info.unwrap().rank_fusion_score, 0.8);
+
+        // pure vector search hits have no text match or highlights
+        let hit: SearchHit<serde_json::Value> =
+            serde_json::from_str(r#"{"document": {}, "vector_distance": 0.5}"#).unwrap();
+        assert_eq!(hit.text_match, 0);
+        assert!(hit.highlights.is_empty());
+    }
+
+    #[test]
+    fn test_joined_search_hit() {
+        #[derive(Debug, serde::Serialize, serde::Deserialize)]
+        struct Order {
+            id: String,
+            amount: u32,
+        }
+
+        #[derive(Debug, serde::Serialize, serde::Deserialize)]
+        struct Customer {
+            name: String,
+        }
+
+        #[derive(Debug, serde::Serialize, serde::Deserialize)]
+        struct OrderJoins {
+            customers: Customer,
+        }
+
+        let json = serde_json::json!({
+            "document": {"id": "1", "amount": 30, "customers": {"name": "Joe"}},
+            "highlights": [],
+            "text_match": 100,
+            "geo_distance_meters": {"location": 12.5},
+        });
+        let hit: SearchHit<Order, OrderJoins> = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(hit.document.id, "1");
+        assert_eq!(hit.document.amount, 30);
+        assert_eq!(hit.joined.customers.name, "Joe");
+
+        // serialized hits, i.e. recorded in cassettes, read back the same
+        assert_eq!(serde_json::to_value(&hit).unwrap(), json);
+        let hit: SearchHit<serde_json::Value> =
+            serde_json::from_value(serde_json::json!({"document": {"title": "shoe"}})).unwrap();
+        assert_eq!(
+            serde_json::to_value(&hit).unwrap(),
+            serde_json::json!({"document": {"title": "shoe"}, "highlights": [], "text_match": 0})
+        );
+
+        let query = SearchQuery::new("*")
+            .include_fields(["id", "amount"])
+            .include_joined_fields("customers", ["name", "email"]);
+        assert_eq!(
+            query.include_fields.as_deref(),
+            Some("id,amount,$customers(name,email)")
+        );
+    }
+}
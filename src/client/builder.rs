@@ -1,26 +1,42 @@
-use super::{Client, NodeConfig, CONTENT_TYPE};
+use super::{Client, NodeConfig, Timeouts, Transport};
 use crate::error::*;
-use reqwest::header::{HeaderMap, HeaderValue};
-use std::env;
+use derivative::Derivative;
+use reqwest::header::HeaderValue;
+use std::{env, time::Duration};
 use tracing::instrument;
 
 pub const TYPESENSE_API_KEY_HEADER_NAME: &str = "X-TYPESENSE-API-KEY";
+pub const TYPESENSE_API_KEY_QUERY_NAME: &str = "x-typesense-api-key";
 pub const JSON_CONTENT_TYPE: HeaderValue = HeaderValue::from_static("application/json");
 
-#[derive(Debug)]
+#[derive(Derivative)]
+#[derivative(Debug)]
 pub struct ClientBuilder {
     hostname: Option<String>,
     reqwest_builder: Option<reqwest::ClientBuilder>,
+    #[derivative(Debug = "ignore")]
     api_key: Option<String>,
+    api_key_in_query: bool,
+    connect_timeout: Option<Duration>,
+    timeouts: Timeouts,
     nodes: Vec<NodeConfig>,
 }
 
+impl Default for ClientBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl ClientBuilder {
     pub fn new() -> Self {
         Self {
             reqwest_builder: None,
             hostname: env::var("TYPESENSE_HOSTNAME").ok(),
             api_key: env::var("TYPESENSE_API_KEY").ok(),
+            api_key_in_query: false,
+            connect_timeout: None,
+            timeouts: Timeouts::default(),
             nodes: Vec::new(),
         }
     }
@@ -35,6 +51,46 @@ impl ClientBuilder {
         self
     }
 
+    /// Send the API key of `GET` requests as the `x-typesense-api-key` query parameter
+    /// instead of a header, for proxies that only forward `GET` requests untouched.
+    pub fn api_key_in_query(mut self, api_key_in_query: bool) -> Self {
+        self.api_key_in_query = api_key_in_query;
+        self
+    }
+
+    /// Timeout for connecting to typesense; only applies to the default reqwest transport.
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout.replace(timeout);
+        self
+    }
+
+    /// Timeout of requests that don't have a timeout for their class of operation.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeouts.default.replace(timeout);
+        self
+    }
+
+    pub fn search_timeout(mut self, timeout: Duration) -> Self {
+        self.timeouts.search.replace(timeout);
+        self
+    }
+
+    pub fn import_timeout(mut self, timeout: Duration) -> Self {
+        self.timeouts.import.replace(timeout);
+        self
+    }
+
+    pub fn export_timeout(mut self, timeout: Duration) -> Self {
+        self.timeouts.export.replace(timeout);
+        self
+    }
+
+    /// Timeout of requests managing collections, aliases and keys.
+    pub fn admin_timeout(mut self, timeout: Duration) -> Self {
+        self.timeouts.admin.replace(timeout);
+        self
+    }
+
     pub fn nodes(mut self, nodes: impl IntoIterator<Item = impl Into<NodeConfig>>) -> Self {
         self.nodes.extend(nodes.into_iter().map(|n| n.into()));
         self
@@ -45,27 +101,40 @@ impl ClientBuilder {
         self
     }
 
-    #[instrument]
-    pub fn build(self) -> Result<Client, Error> {
-        let api_key = self.api_key.ok_or(Error::ApiKeyNotFound)?;
-        let hostname = self.hostname.ok_or(Error::HostnameNotFound)?;
-
-        let mut builder = self.reqwest_builder.unwrap_or_default();
+    #[instrument(skip(self))]
+    pub fn build(mut self) -> Result<Client, Error> {
+        let mut builder = self.reqwest_builder.take().unwrap_or_default();
+        if let Some(timeout) = self.connect_timeout {
+            builder = builder.connect_timeout(timeout);
+        }
+        let reqwest = builder.build().toss_reqwest_builder_failed()?;
 
-        let mut header_map = HeaderMap::new();
-        header_map.insert(
-            TYPESENSE_API_KEY_HEADER_NAME,
-            HeaderValue::from_str(&api_key).toss_invalid_api_key_with(|| api_key.to_owned())?,
-        );
-        header_map.insert(CONTENT_TYPE, JSON_CONTENT_TYPE);
-        builder = builder.default_headers(header_map);
+        self.build_with_transport(reqwest)
+    }
 
-        let reqwest = builder.build().toss_reqwest_builder_failed()?;
+    /// Build a client sending its requests through `transport` instead of reqwest.
+    ///
+    /// The reqwest builder is ignored.
+    #[instrument(skip(self, transport))]
+    pub fn build_with_transport<H: Transport>(self, transport: H) -> Result<Client<H>, Error> {
+        let api_key = self.api_key.ok_or(Error::ApiKeyNotFound)?;
+        let hostname = self.hostname.ok_or(Error::HostnameNotFound)?;
 
+        // the api key is set per request, so clients with other keys can share the pool
         Ok(Client {
-            reqwest,
+            transport,
+            api_key_header: api_key_header(&api_key)?,
             api_key: api_key.into(),
+            api_key_in_query: self.api_key_in_query,
+            timeouts: self.timeouts,
             hostname: hostname.into(),
         })
     }
 }
+
+pub(crate) fn api_key_header(api_key: &str) -> Result<HeaderValue, Error> {
+    let mut header =
+        HeaderValue::from_str(api_key).toss_invalid_api_key_with(|| api_key.to_owned())?;
+    header.set_sensitive(true);
+    Ok(header)
+}
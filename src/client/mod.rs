@@ -11,29 +11,39 @@ use crate::{
 };
 use bytes::Bytes;
 use derivative::Derivative;
-use reqwest::{header::CONTENT_TYPE, Client as Reqwest, RequestBuilder};
-use serde::{de::DeserializeOwned, Deserialize, Serialize};
-use std::{
-    any::type_name,
-    fmt::{self, Write},
+use reqwest::{
+    header::{HeaderMap, HeaderValue, CONTENT_TYPE},
+    Client as Reqwest, Method,
 };
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{any::type_name, fmt, time::Duration};
 use std::{iter::once, sync::Arc};
 use tracing::instrument;
 
 pub mod builder;
 mod node_config;
+mod timeouts;
+mod transport;
 use builder::*;
 pub use node_config::*;
+pub use timeouts::*;
+pub use transport::*;
+
+const TEXT_CONTENT_TYPE: HeaderValue = HeaderValue::from_static("text/plain");
 
 type QueryPair<Q, const N: usize> = [(&'static str, Q); N];
 
 #[derive(Clone, Derivative)]
 #[derivative(Debug)]
-pub struct Client {
-    pub reqwest: Reqwest,
+pub struct Client<H = Reqwest> {
+    pub transport: H,
     hostname: Arc<str>,
     #[derivative(Debug = "ignore")]
     api_key: Arc<str>,
+    #[derivative(Debug = "ignore")]
+    api_key_header: HeaderValue,
+    api_key_in_query: bool,
+    timeouts: Timeouts,
 }
 
 impl Client {
@@ -48,7 +58,9 @@ impl Client {
     pub fn builder() -> ClientBuilder {
         ClientBuilder::new()
     }
+}
 
+impl<H: Transport> Client<H> {
     pub fn hostname(&self) -> &str {
         &self.hostname
     }
@@ -57,7 +69,43 @@ impl Client {
         &self.api_key
     }
 
-    pub fn keys<'a>(&'a self) -> Keys<'a> {
+    pub fn timeouts(&self) -> &Timeouts {
+        &self.timeouts
+    }
+
+    /// Client sending `api_key` instead, i.e. a tenant's scoped search key.
+    ///
+    /// The returned client shares the connection pool with `self`.
+    pub fn with_api_key(&self, api_key: impl AsRef<str>) -> Result<Self, Error> {
+        let api_key = api_key.as_ref();
+
+        Ok(Self {
+            api_key_header: api_key_header(api_key)?,
+            api_key: api_key.into(),
+            ..self.clone()
+        })
+    }
+
+    /// Client sending every request with `timeout`, instead of the timeout of its class of
+    /// operation.
+    ///
+    /// The returned client shares the connection pool with `self`.
+    pub fn with_timeout(&self, timeout: Duration) -> Self {
+        let timeout = Some(timeout);
+
+        Self {
+            timeouts: Timeouts {
+                default: timeout,
+                search: timeout,
+                import: timeout,
+                export: timeout,
+                admin: timeout,
+            },
+            ..self.clone()
+        }
+    }
+
+    pub fn keys<'a>(&'a self) -> Keys<'a, H> {
         Keys::new(self)
     }
 
@@ -85,16 +133,19 @@ impl Client {
         self.get(["aliases"]).await
     }
 
-    pub fn collection<'a, T: Typesense>(&'a self, collection_name: &'a str) -> Collection<'a, T> {
+    pub fn collection<'a, T: Typesense>(
+        &'a self,
+        collection_name: &'a str,
+    ) -> Collection<'a, T, H> {
         Collection::new(self, collection_name)
     }
 
-    pub fn documents<'a, T: Typesense>(&'a self, collection_name: &'a str) -> Documents<'a, T> {
+    pub fn documents<'a, T: Typesense>(&'a self, collection_name: &'a str) -> Documents<'a, T, H> {
         Documents::new(self, collection_name)
     }
 }
 
-impl Client {
+impl<H: Transport> Client<H> {
     #[instrument]
     pub(crate) async fn get<'a, B, P, Q, const N: usize, R>(
         &self,
@@ -106,8 +157,45 @@ impl Client {
         Q: Serialize + fmt::Debug,
         R: DeserializeOwned,
     {
-        self.action(path_query_body, |url| self.reqwest.get(url))
-            .await
+        self.action(Method::GET, path_query_body).await
+    }
+
+    #[instrument(skip(query))]
+    pub(crate) async fn get_with_query<'a, P, Q, R>(&self, path: P, query: &Q) -> Result<R, Error>
+    where
+        P: IntoIterator<Item = &'a str> + fmt::Debug,
+        Q: Serialize + ?Sized,
+        R: DeserializeOwned,
+    {
+        let req = self.request(Method::GET, path, query, Bytes::new(), JSON_CONTENT_TYPE)?;
+
+        self.execute(req).await
+    }
+
+    /// Every line of the response parsed as json, i.e. for exports.
+    #[instrument]
+    pub(crate) async fn get_lines<'a, P, R>(&'a self, path: P) -> Result<Vec<R>, Error>
+    where
+        P: IntoIterator<Item = &'a str> + fmt::Debug,
+        R: DeserializeOwned,
+    {
+        let body = self
+            .action_raw(
+                Method::GET,
+                path,
+                Bytes::new(),
+                [] as QueryPair<&str, 0>,
+                None,
+            )
+            .await?;
+
+        body.lines()
+            .map(|line| {
+                serde_json::from_str::<TypesenseResult<R>>(line)
+                    .toss_deserialize_text_with(|| line.to_owned())?
+                    .into_res()
+            })
+            .collect()
     }
 
     #[instrument]
@@ -121,8 +209,7 @@ impl Client {
         Q: Serialize + fmt::Debug,
         R: DeserializeOwned,
     {
-        self.action(path_query_body, |url| self.reqwest.post(url))
-            .await
+        self.action(Method::POST, path_query_body).await
     }
 
     #[instrument(skip(body))]
@@ -131,12 +218,13 @@ impl Client {
         path: P,
         body: impl Into<Bytes>,
         query: QueryPair<Q, N>,
+        timeout: Option<Duration>,
     ) -> Result<String, Error>
     where
         P: IntoIterator<Item = &'a str> + fmt::Debug,
         Q: Serialize + fmt::Debug,
     {
-        self.action_raw(path, body, query, |url| self.reqwest.post(url))
+        self.action_raw(Method::POST, path, body, query, timeout)
             .await
     }
 
@@ -151,8 +239,7 @@ impl Client {
         Q: Serialize + fmt::Debug,
         R: DeserializeOwned,
     {
-        self.action(path_query_body, |url| self.reqwest.patch(url))
-            .await
+        self.action(Method::PATCH, path_query_body).await
     }
 
     #[instrument(skip(body))]
@@ -161,12 +248,13 @@ impl Client {
         path: P,
         body: impl Into<Bytes>,
         query: QueryPair<Q, N>,
+        timeout: Option<Duration>,
     ) -> Result<String, Error>
     where
         P: IntoIterator<Item = &'a str> + fmt::Debug,
         Q: Serialize + fmt::Debug,
     {
-        self.action_raw(path, body, query, |url| self.reqwest.patch(url))
+        self.action_raw(Method::PATCH, path, body, query, timeout)
             .await
     }
 
@@ -181,12 +269,11 @@ impl Client {
         Q: Serialize + fmt::Debug,
         R: DeserializeOwned,
     {
-        self.action(path_query_body, |url| self.reqwest.put(url))
-            .await
+        self.action(Method::PUT, path_query_body).await
     }
 
     #[instrument]
-    pub(crate) async fn delete<'a, P, B, Q, const N: usize, R>(
+    pub(crate) async fn delete<'a, B, P, Q, const N: usize, R>(
         &self,
         path_query_body: impl Into<BodyPathQuery<'a, B, P, Q, N>> + fmt::Debug,
     ) -> Result<R, Error>
@@ -196,65 +283,127 @@ impl Client {
         Q: Serialize + fmt::Debug,
         R: DeserializeOwned,
     {
-        self.action(path_query_body, |url| self.reqwest.delete(url))
+        self.action(Method::DELETE, path_query_body).await
+    }
+
+    /// Builds a request to `hostname/path?query` authenticated with the api key of this client,
+    /// with the timeout of its class of operation.
+    pub(crate) fn request<'a, Q>(
+        &self,
+        method: Method,
+        path: impl IntoIterator<Item = &'a str>,
+        query: &Q,
+        body: Bytes,
+        content_type: HeaderValue,
+    ) -> Result<Request, Error>
+    where
+        Q: Serialize + ?Sized,
+    {
+        let path = path.into_iter().collect::<Vec<_>>();
+        let timeout = self.timeouts.for_path(&path);
+
+        let mut url = self.hostname.to_string();
+        path.into_iter().for_each(|p| {
+            url.push('/');
+            url.push_str(p);
+        });
+
+        let mut query = serde_urlencoded::to_string(query).toss_query_to_url()?;
+
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_TYPE, content_type);
+
+        if self.api_key_in_query && method == Method::GET {
+            let api_key = [(TYPESENSE_API_KEY_QUERY_NAME, self.api_key.as_ref())];
+            if !query.is_empty() {
+                query.push('&');
+            }
+            query.push_str(&serde_urlencoded::to_string(api_key).toss_query_to_url()?);
+        } else {
+            headers.insert(TYPESENSE_API_KEY_HEADER_NAME, self.api_key_header.clone());
+        }
+
+        if !query.is_empty() {
+            url.push('?');
+            url.push_str(&query);
+        }
+
+        Ok(Request {
+            method,
+            url,
+            headers,
+            body,
+            timeout,
+        })
+    }
+
+    async fn send(&self, request: Request) -> Result<Bytes, Error> {
+        let res = self
+            .transport
+            .send(request)
             .await
+            .map_err(|e| Error::ActionFailed(Box::new(e)))?;
+
+        Ok(res.body)
+    }
+
+    async fn execute<R: DeserializeOwned>(&self, request: Request) -> Result<R, Error> {
+        let body = self.send(request).await?;
+
+        let res: TypesenseResult<R> = serde_json::from_slice(&body)
+            .toss_deserialize_text_with(|| String::from_utf8_lossy(&body).into_owned())?;
+
+        res.into_res()
     }
 
-    async fn action<'a, B, P, Q, const N: usize, R, F>(
+    async fn action<'a, B, P, Q, const N: usize, R>(
         &self,
+        method: Method,
         path_query_body: impl Into<BodyPathQuery<'a, B, P, Q, N>> + fmt::Debug,
-        f: F,
     ) -> Result<R, Error>
     where
         B: Serialize + fmt::Debug,
         P: IntoIterator<Item = &'a str>,
         Q: Serialize + fmt::Debug,
         R: DeserializeOwned,
-        F: FnOnce(&str) -> RequestBuilder,
     {
-        let res: TypesenseResult<R> = path_query_body
-            .into()
-            .build(self.hostname.as_ref(), f)
-            .send()
-            .await
-            .toss_action_failed()?
-            .json()
-            .await
-            .toss_deserialize_body()?;
+        let BodyPathQuery { body, path, query } = path_query_body.into();
 
-        res.into_res()
+        let body = match body {
+            Some(body) => serde_json::to_vec(&body).toss_body_to_json()?.into(),
+            None => Bytes::new(),
+        };
+
+        let req = self.request(method, path, query.as_slice(), body, JSON_CONTENT_TYPE)?;
+
+        self.execute(req).await
     }
 
-    async fn action_raw<'a, P, Q, const N: usize, F>(
+    async fn action_raw<'a, P, Q, const N: usize>(
         &'a self,
+        method: Method,
         path: P,
         body: impl Into<Bytes>,
         query: QueryPair<Q, N>,
-        f: F,
+        timeout: Option<Duration>,
     ) -> Result<String, Error>
     where
         P: IntoIterator<Item = &'a str> + fmt::Debug,
         Q: Serialize + fmt::Debug,
-        F: FnOnce(&str) -> RequestBuilder,
     {
-        let hostname = self.hostname.as_ref();
-        let path = path.into_iter();
-
-        let mut url = String::with_capacity(hostname.len() + 1 + path.size_hint().0);
-        write!(&mut url, "{}", hostname).unwrap();
-        path.for_each(|p| {
-            write!(&mut url, "/{}", p).unwrap();
-        });
-
-        let req = f(&url).body(body.into()).header(CONTENT_TYPE, "text/plain");
+        let mut req = self.request(
+            method,
+            path,
+            query.as_slice(),
+            body.into(),
+            TEXT_CONTENT_TYPE,
+        )?;
+        if timeout.is_some() {
+            req.timeout = timeout;
+        }
+        let body = self.send(req).await?;
 
-        req.query(query.as_ref())
-            .send()
-            .await
-            .toss_action_failed()?
-            .text()
-            .await
-            .toss_deserialize_body()
+        Ok(String::from_utf8_lossy(&body).into_owned())
     }
 }
 
@@ -270,37 +419,6 @@ where
     query: QueryPair<Q, N>,
 }
 
-impl<'a, B, P, Q, const N: usize> BodyPathQuery<'a, B, P, Q, N>
-where
-    B: Serialize + fmt::Debug,
-    P: IntoIterator<Item = &'a str>,
-    Q: Serialize + fmt::Debug,
-{
-    pub fn build<F>(self, hostname: &str, f: F) -> RequestBuilder
-    where
-        F: FnOnce(&str) -> RequestBuilder,
-    {
-        let Self { path, query, body } = self;
-        let path = path.into_iter();
-
-        let mut url = String::with_capacity(hostname.len() + 1 + path.size_hint().0);
-        write!(&mut url, "{}", hostname).unwrap();
-        path.for_each(|p| {
-            write!(&mut url, "/{}", p).unwrap();
-        });
-
-        let req = f(&url);
-
-        let req = if let Some(body) = body {
-            req.json(&body)
-        } else {
-            req
-        };
-
-        req.query(query.as_ref())
-    }
-}
-
 impl<'a, P> From<P> for BodyPathQuery<'a, (), P>
 where
     P: IntoIterator<Item = &'a str>,
@@ -375,3 +493,188 @@ impl<T> TypesenseResult<T> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{convert::Infallible, sync::Mutex};
+
+    #[test]
+    fn test_api_key_override() {
+        let client = Client::new("http://localhost:8108", "admin");
+        let tenant = client.with_api_key("scoped").unwrap();
+        assert_eq!(client.api_key(), "admin");
+        assert_eq!(tenant.api_key(), "scoped");
+
+        let req = tenant
+            .request(
+                Method::GET,
+                ["collections"],
+                &(),
+                Bytes::new(),
+                JSON_CONTENT_TYPE,
+            )
+            .unwrap();
+        assert_eq!(req.headers[TYPESENSE_API_KEY_HEADER_NAME], "scoped");
+        assert_eq!(req.url, "http://localhost:8108/collections");
+
+        assert!(client.with_api_key("invalid\n").is_err());
+    }
+
+    #[test]
+    fn test_api_key_in_query() {
+        let client = Client::builder()
+            .hostname("http://localhost:8108")
+            .api_key("admin key")
+            .api_key_in_query(true)
+            .build()
+            .unwrap();
+
+        let query = [("action", Some("upsert")), ("batch_size", None)];
+        let req = client
+            .request(
+                Method::GET,
+                ["keys"],
+                &query,
+                Bytes::new(),
+                JSON_CONTENT_TYPE,
+            )
+            .unwrap();
+        assert_eq!(
+            req.url,
+            "http://localhost:8108/keys?action=upsert&x-typesense-api-key=admin+key"
+        );
+        assert!(req.headers.get(TYPESENSE_API_KEY_HEADER_NAME).is_none());
+        // logged requests don't leak the key
+        let debug = format!("{req:?}");
+        assert!(debug.contains("action=upsert&x-typesense-api-key=[redacted]"));
+        assert!(!debug.contains("admin+key"));
+        let builder = Client::builder().api_key("admin key");
+        assert!(!format!("{builder:?}").contains("admin key"));
+
+        let req = client
+            .request(Method::POST, ["keys"], &(), Bytes::new(), JSON_CONTENT_TYPE)
+            .unwrap();
+        assert_eq!(req.headers[TYPESENSE_API_KEY_HEADER_NAME], "admin key");
+    }
+
+    #[derive(Debug, Clone, Default)]
+    struct MockTransport {
+        requests: Arc<Mutex<Vec<Request>>>,
+    }
+
+    impl Transport for MockTransport {
+        type Error = Infallible;
+
+        async fn send(&self, request: Request) -> Result<Response, Self::Error> {
+            self.requests.lock().unwrap().push(request);
+
+            Ok(Response {
+                status: reqwest::StatusCode::OK,
+                headers: HeaderMap::new(),
+                body: Bytes::from_static(br#"{"aliases": []}"#),
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_custom_transport() {
+        let transport = MockTransport::default();
+        let client = Client::builder()
+            .hostname("http://localhost:8108")
+            .api_key("xyz")
+            .build_with_transport(transport.clone())
+            .unwrap();
+
+        let res = client.retrieve_aliases().await.unwrap();
+        assert!(res.aliases.is_empty());
+
+        let requests = transport.requests.lock().unwrap();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].method, Method::GET);
+        assert_eq!(requests[0].url, "http://localhost:8108/aliases");
+        assert_eq!(requests[0].headers[TYPESENSE_API_KEY_HEADER_NAME], "xyz");
+    }
+
+    #[tokio::test]
+    async fn test_timeouts() {
+        use crate::Typesense;
+
+        #[derive(Debug, Clone, Serialize, Deserialize, Typesense)]
+        struct Book {
+            id: String,
+            title: String,
+        }
+
+        let transport = MockTransport::default();
+        let client = Client::builder()
+            .hostname("http://localhost:8108")
+            .api_key("xyz")
+            .timeout(Duration::from_secs(10))
+            .import_timeout(Duration::from_secs(300))
+            .admin_timeout(Duration::from_secs(30))
+            .build_with_transport(transport.clone())
+            .unwrap();
+
+        client.retrieve_aliases().await.unwrap();
+        let books = client.documents::<Book>("books");
+        let _ = books.retrieve(&"1".into()).await;
+        let _ = books.batch_upsert(&[]).await;
+        let _ = books
+            .batch_upsert(&[])
+            .batch_size("10")
+            .timeout(Duration::from_secs(600))
+            .await;
+
+        // overrides on the client and its request builders
+        let query = crate::SearchQuery::new("*");
+        let _ = books
+            .clone()
+            .timeout(Duration::from_secs(1))
+            .search(&query)
+            .await;
+        let _ = books
+            .clone()
+            .timeout(Duration::from_secs(1))
+            .retrieve(&"1".into())
+            .await;
+        let _ = books
+            .clone()
+            .timeout(Duration::from_secs(900))
+            .export()
+            .await;
+        let collection = client.collection::<Book>("books");
+        let _ = collection.timeout(Duration::from_secs(5)).retreive().await;
+        let _ = client
+            .keys()
+            .timeout(Duration::from_secs(5))
+            .retreive_all()
+            .await;
+        client
+            .with_timeout(Duration::from_secs(5))
+            .retrieve_aliases()
+            .await
+            .unwrap();
+
+        let requests = transport.requests.lock().unwrap();
+        let timeouts = requests.iter().map(|r| r.timeout).collect::<Vec<_>>();
+        let secs = |s| Some(Duration::from_secs(s));
+        assert_eq!(
+            timeouts,
+            [
+                secs(30),
+                secs(10),
+                secs(300),
+                secs(600),
+                secs(1),
+                secs(1),
+                secs(900),
+                secs(5),
+                secs(5),
+                secs(5)
+            ]
+        );
+        assert!(requests[3].url.ends_with("action=upsert&batch_size=10"));
+        assert!(requests[6].url.ends_with("/documents/export"));
+    }
+}
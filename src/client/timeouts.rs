@@ -0,0 +1,62 @@
+use std::time::Duration;
+
+/// Request timeouts by class of operation, set on [`ClientBuilder`](super::ClientBuilder).
+///
+/// Operations without a timeout of their own use `default`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Timeouts {
+    pub default: Option<Duration>,
+    /// searches and multi-searches
+    pub search: Option<Duration>,
+    /// document imports, including batch actions
+    pub import: Option<Duration>,
+    pub export: Option<Duration>,
+    /// managing collections, aliases and keys
+    pub admin: Option<Duration>,
+}
+
+impl Timeouts {
+    /// Timeout of a request to `path`.
+    pub(crate) fn for_path(&self, path: &[&str]) -> Option<Duration> {
+        let class = match path {
+            ["multi_search"] | ["collections", _, "documents", "search"] => self.search,
+            ["collections", _, "documents", "import"] => self.import,
+            ["collections", _, "documents", "export"] => self.export,
+            ["collections", _, "documents", ..] => None,
+            ["collections", ..] | ["aliases", ..] | ["keys", ..] => self.admin,
+            _ => None,
+        };
+
+        class.or(self.default)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timeout_for_path() {
+        let secs = |s| Some(Duration::from_secs(s));
+        let timeouts = Timeouts {
+            default: secs(10),
+            search: secs(1),
+            import: secs(300),
+            export: None,
+            admin: secs(30),
+        };
+
+        assert_eq!(timeouts.for_path(&["multi_search"]), secs(1));
+        let search = ["collections", "books", "documents", "search"];
+        assert_eq!(timeouts.for_path(&search), secs(1));
+        let import = ["collections", "books", "documents", "import"];
+        assert_eq!(timeouts.for_path(&import), secs(300));
+        let export = ["collections", "books", "documents", "export"];
+        assert_eq!(timeouts.for_path(&export), secs(10));
+        let document = ["collections", "books", "documents", "1"];
+        assert_eq!(timeouts.for_path(&document), secs(10));
+        assert_eq!(timeouts.for_path(&["collections", "books"]), secs(30));
+        assert_eq!(timeouts.for_path(&["keys"]), secs(30));
+        assert_eq!(timeouts.for_path(&["health"]), secs(10));
+    }
+}
@@ -0,0 +1,103 @@
+use super::builder::TYPESENSE_API_KEY_QUERY_NAME;
+use bytes::Bytes;
+use derivative::Derivative;
+use reqwest::{header::HeaderMap, Client as Reqwest, Method, StatusCode};
+use std::{error::Error as StdError, fmt, future::Future, time::Duration};
+
+/// HTTP stack used by [`Client`](super::Client) to send requests to typesense.
+///
+/// Implemented for [`reqwest::Client`], which is the default.
+/// Implement it to plug in another HTTP client, a mock or a recording transport.
+pub trait Transport: fmt::Debug + Clone + Send + Sync + 'static {
+    type Error: StdError + Send + Sync + 'static;
+
+    fn send(&self, request: Request) -> impl Future<Output = Result<Response, Self::Error>> + Send;
+}
+
+/// The api key is redacted from the url in the `Debug` output.
+#[derive(Clone, PartialEq, Derivative)]
+#[derivative(Debug)]
+pub struct Request {
+    pub method: Method,
+    /// full url, including the query string
+    #[derivative(Debug(format_with = "fmt_redacted_url"))]
+    pub url: String,
+    pub headers: HeaderMap,
+    pub body: Bytes,
+    /// time allowed for the whole request, including reading the response
+    pub timeout: Option<Duration>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Response {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: Bytes,
+}
+
+impl Transport for Reqwest {
+    type Error = reqwest::Error;
+
+    fn send(&self, request: Request) -> impl Future<Output = Result<Response, Self::Error>> + Send {
+        let Request {
+            method,
+            url,
+            headers,
+            body,
+            timeout,
+        } = request;
+
+        let mut req = self.request(method, url).headers(headers).body(body);
+        if let Some(timeout) = timeout {
+            req = req.timeout(timeout);
+        }
+
+        async move {
+            let res = req.send().await.map_err(redact_error_url)?;
+            let status = res.status();
+            let headers = res.headers().clone();
+            let body = res.bytes().await.map_err(redact_error_url)?;
+
+            Ok(Response {
+                status,
+                headers,
+                body,
+            })
+        }
+    }
+}
+
+/// `url` with the value of the api key query parameter, if any, replaced.
+pub(crate) fn redact_url(url: &str) -> String {
+    let Some((path, query)) = url.split_once('?') else {
+        return url.to_owned();
+    };
+
+    let query = query
+        .split('&')
+        .map(|pair| match pair.split_once('=') {
+            Some((TYPESENSE_API_KEY_QUERY_NAME, _)) => {
+                format!("{TYPESENSE_API_KEY_QUERY_NAME}=[redacted]")
+            }
+            _ => pair.to_owned(),
+        })
+        .collect::<Vec<_>>()
+        .join("&");
+
+    format!("{path}?{query}")
+}
+
+fn fmt_redacted_url(url: &str, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    fmt::Debug::fmt(&redact_url(url), f)
+}
+
+// reqwest errors display their url
+fn redact_error_url(mut error: reqwest::Error) -> reqwest::Error {
+    if let Some(url) = error.url_mut() {
+        if let Ok(redacted) = redact_url(url.as_str()).parse() {
+            *url = redacted;
+        }
+    }
+
+    error
+}
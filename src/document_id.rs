@@ -0,0 +1,158 @@
+//! Document ids, see [`Typesense::Id`](crate::Typesense::Id).
+//!
+//! Typesense ids are always strings, so numeric or uuid ids are sent as their [`Display`]
+//! and parsed back with [`FromStr`]; use this module with `#[serde(with = "typesensei::document_id")]`
+//! to store them as strings in documents too.
+//!
+//! Any field can be the id with `#[typesensei(id)]`, as long as serde serializes it as `id`,
+//! i.e. with `#[serde(rename = "id")]`: the derive can't rename fields in serde's output.
+
+use serde::{de::Error as _, Deserialize, Deserializer, Serializer};
+use std::{
+    fmt::{self, Display},
+    str::FromStr,
+};
+
+pub trait DocumentId: fmt::Debug + Display + FromStr + Clone {}
+
+impl<T: fmt::Debug + Display + FromStr + Clone> DocumentId for T {}
+
+pub fn serialize<T, S>(id: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: Display,
+    S: Serializer,
+{
+    serializer.collect_str(id)
+}
+
+pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    T: FromStr,
+    T::Err: Display,
+    D: Deserializer<'de>,
+{
+    String::deserialize(deserializer)?
+        .parse()
+        .map_err(D::Error::custom)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Client, Partial, Request, Response, Transport, Typesense};
+    use bytes::Bytes;
+    use reqwest::{header::HeaderMap, StatusCode};
+    use serde::{Deserialize, Serialize};
+    use serde_json::json;
+    use std::{
+        convert::Infallible,
+        sync::{Arc, Mutex},
+    };
+
+    #[derive(Debug, Serialize, Deserialize, Typesense)]
+    struct Book {
+        #[typesensei(id)]
+        #[serde(rename = "id", with = "crate::document_id")]
+        isbn: u64,
+        title: String,
+    }
+
+    #[test]
+    fn test_numeric_id() {
+        let book = Book {
+            isbn: 9780441013593,
+            title: "Dune".to_owned(),
+        };
+        assert_eq!(book.id(), Some(9780441013593));
+
+        let json = serde_json::to_value(&book).unwrap();
+        assert_eq!(json, json!({"id": "9780441013593", "title": "Dune"}));
+        let book = serde_json::from_value::<Book>(json).unwrap();
+        assert_eq!(book.isbn, 9780441013593);
+
+        let schema = Book::schema("books");
+        assert!(schema.fields.iter().all(|f| f.name != "id"));
+
+        assert!(serde_json::from_value::<Book>(json!({"id": "dune", "title": "Dune"})).is_err());
+
+        // partials, i.e. returned by `create` or sent by `import`, store ids as strings too
+        let partial = book.into_partial();
+        let json = serde_json::to_value(&partial).unwrap();
+        assert_eq!(json, json!({"id": "9780441013593", "title": "Dune"}));
+        let partial = serde_json::from_value::<<Book as Partial>::Partial>(json).unwrap();
+        assert_eq!(partial.isbn, Some(9780441013593));
+        let partial = serde_json::from_value::<<Book as Partial>::Partial>(json!({})).unwrap();
+        assert_eq!(partial.isbn, None);
+    }
+
+    // replies to every request with the stored book
+    #[derive(Debug, Clone, Default)]
+    struct BookTransport {
+        requests: Arc<Mutex<Vec<Request>>>,
+    }
+
+    impl Transport for BookTransport {
+        type Error = Infallible;
+
+        async fn send(&self, request: Request) -> Result<Response, Self::Error> {
+            self.requests.lock().unwrap().push(request);
+
+            Ok(Response {
+                status: StatusCode::OK,
+                headers: HeaderMap::new(),
+                body: Bytes::from_static(br#"{"id": "9780441013593", "title": "Dune"}"#),
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_numeric_id_requests() {
+        let transport = BookTransport::default();
+        let client = Client::builder()
+            .hostname("http://localhost:8108")
+            .api_key("xyz")
+            .build_with_transport(transport.clone())
+            .unwrap();
+
+        let books = client.documents::<Book>("books");
+        let book = Book {
+            isbn: 9780441013593,
+            title: "Dune".to_owned(),
+        };
+        let created = books.create(&book).await.unwrap();
+        assert_eq!(created.isbn, Some(9780441013593));
+        let partial = Book::partial().with_title("Dune Messiah".to_owned());
+        books.update(9780441013593, &partial).await.unwrap();
+        assert_eq!(
+            books.retrieve(&9780441013593).await.unwrap().isbn,
+            9780441013593
+        );
+
+        let requests = transport.requests.lock().unwrap();
+        let body = serde_json::from_slice::<serde_json::Value>(&requests[0].body).unwrap();
+        assert_eq!(body, json!({"id": "9780441013593", "title": "Dune"}));
+        let url = "http://localhost:8108/collections/books/documents/9780441013593";
+        assert!(requests[1].url.starts_with(url));
+        assert_eq!(requests[2].url, url);
+    }
+
+    #[cfg(feature = "testing")]
+    #[tokio::test]
+    async fn test_numeric_id_documents() {
+        let client = crate::testing::MockServer::new().client();
+        client.collection::<Book>("books").create().await.unwrap();
+
+        let books = client.documents::<Book>("books");
+        let book = Book {
+            isbn: 9780441013593,
+            title: "Dune".to_owned(),
+        };
+        let created = books.create(&book).await.unwrap();
+        assert_eq!(created.isbn, Some(9780441013593));
+        books.upsert(&book).await.unwrap();
+        books.update_document(&book).await.unwrap();
+        let partial = Book::partial().with_title("Dune Messiah".to_owned());
+        books.update(9780441013593, &partial).await.unwrap();
+        books.batch_upsert(&[book.into_partial()]).await.unwrap();
+        assert_eq!(books.retrieve(9780441013593).await.unwrap().title, "Dune");
+    }
+}
@@ -0,0 +1,94 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+
+/// Value of a `geopoint` field, sent to typesense as `[lat, lng]`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, PartialOrd)]
+pub struct GeoPoint {
+    pub lat: f64,
+    pub lng: f64,
+}
+
+impl GeoPoint {
+    pub fn new(lat: f64, lng: f64) -> Self {
+        Self { lat, lng }
+    }
+}
+
+impl From<(f64, f64)> for GeoPoint {
+    fn from((lat, lng): (f64, f64)) -> Self {
+        Self { lat, lng }
+    }
+}
+
+impl From<[f64; 2]> for GeoPoint {
+    fn from([lat, lng]: [f64; 2]) -> Self {
+        Self { lat, lng }
+    }
+}
+
+impl fmt::Display for GeoPoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}, {}", self.lat, self.lng)
+    }
+}
+
+impl Serialize for GeoPoint {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        [self.lat, self.lng].serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for GeoPoint {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        <[f64; 2]>::deserialize(deserializer).map(Self::from)
+    }
+}
+
+/// Distance used in geo filters and geo sorting.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub enum Distance {
+    Km(f64),
+    Mi(f64),
+}
+
+impl Distance {
+    pub fn value(&self) -> f64 {
+        match self {
+            Self::Km(d) | Self::Mi(d) => *d,
+        }
+    }
+
+    pub fn unit(&self) -> &'static str {
+        match self {
+            Self::Km(_) => "km",
+            Self::Mi(_) => "mi",
+        }
+    }
+}
+
+impl fmt::Display for Distance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.value(), self.unit())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_geopoint_serde() {
+        let point = GeoPoint::new(48.853, 2.344);
+        assert_eq!(serde_json::to_string(&point).unwrap(), "[48.853,2.344]");
+        assert_eq!(
+            serde_json::from_str::<GeoPoint>("[48.853,2.344]").unwrap(),
+            point
+        );
+    }
+}
@@ -1,47 +1,105 @@
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
+// lets the derive macros refer to `::typesensei` from within this crate
+extern crate self as typesensei;
+
 mod client;
 
 pub mod api;
+pub mod document_id;
+pub mod geo;
 pub mod partial;
 pub mod schema;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+pub mod timestamp;
 
-pub use crate::field_trait::TypesenseField;
-pub use api::keys::{generate_scoped_search_key, ApiKey};
+pub use crate::field_trait::{EnumField, TypesenseField};
+pub use api::keys::{decode_scoped_search_key, generate_scoped_search_key, Action, ApiKey};
+pub use api::search::{FieldRef, Filter, SearchQuery, Sort, VectorQuery};
 pub use client::*;
+pub use document_id::DocumentId;
 pub use error::Error;
-pub use partial::Partial;
+pub use geo::GeoPoint;
+pub use partial::{Partial, Patch};
 pub use reqwest::{Client as Reqwest, ClientBuilder as ReqwestBuilder};
-pub use typesensei_derive::{Partial, Typesense};
+pub use timestamp::Timestamp;
+pub use typesensei_derive::{Partial, Typesense, TypesenseField};
 
 pub trait Typesense
 where
     Self: fmt::Debug + Serialize + partial::Partial,
     for<'de> Self: Deserialize<'de>,
 {
+    /// Type of the `id` field, or `String` for documents without one.
+    ///
+    /// The derive takes the field serialized as `id`, or the one marked `#[typesensei(id)]`,
+    /// which must be serialized as `id` too, see [`document_id`].
+    type Id: DocumentId;
+
+    /// Id of this document, `None` if typesense generates it.
+    fn id(&self) -> Option<Self::Id>;
+
     fn schema<'a>(collection_name: &'a str) -> schema::CollectionSchema<'a>;
 
-    fn partial() -> Self::Partial;
+    /// Empty partial of a struct document; enum documents start from the partial of a variant,
+    /// as the tag of its variant is always sent.
+    fn partial() -> Self::Partial
+    where
+        Self::Partial: Default,
+    {
+        Default::default()
+    }
 }
 
 mod field_trait {
-    use crate::schema::Field;
-
+    use crate::{geo::GeoPoint, schema::Field, timestamp::Timestamp};
+    use std::{
+        borrow::Cow,
+        collections::{BTreeSet, HashSet},
+        num::*,
+        sync::Arc,
+    };
+
+    #[diagnostic::on_unimplemented(
+        message = "`{Self}` can't be stored as a typesense field",
+        note = "dates are stored as `typesensei::Timestamp`s, or with a `typesensei::timestamp` serde adapter and `#[typesensei(timestamp)]`",
+        note = "other types can set their typesense type with `#[typesensei(ty = \"...\")]`"
+    )]
     pub trait TypesenseField {
         const TYPE: &'static str;
+        /// Whether the field is sortable when not set with `#[typesensei(sort)]`,
+        /// `None` to leave it to typesense.
+        const SORT: Option<bool> = None;
+    }
+
+    /// Unit enum stored as a string field, derived with `#[derive(TypesenseField)]`.
+    pub trait EnumField: TypesenseField {
+        /// Values of the variants as they are stored, honoring serde's `rename_all`.
+        const VALUES: &'static [&'static str];
+
+        fn as_str(&self) -> &'static str;
+    }
+
+    // the derive can't implement this for `Vec` of the enum
+    impl<E: EnumField> TypesenseField for Vec<E> {
+        const TYPE: &'static str = Field::STRING_ARRAY;
     }
 
     impl<T: TypesenseField> TypesenseField for &T {
         const TYPE: &'static str = T::TYPE;
+        const SORT: Option<bool> = T::SORT;
     }
 
     impl<T: TypesenseField> TypesenseField for &mut T {
         const TYPE: &'static str = T::TYPE;
+        const SORT: Option<bool> = T::SORT;
     }
 
     impl<T: TypesenseField> TypesenseField for Option<T> {
         const TYPE: &'static str = T::TYPE;
+        const SORT: Option<bool> = T::SORT;
     }
 
     macro_rules! impl_field {
@@ -60,16 +118,66 @@ mod field_trait {
 
     impl_field!(u8, u16, i8, i16, i32 => Field::INT32, Field::INT32_ARRAY);
     impl_field!(u32, u64, usize, i64, isize => Field::INT64, Field::INT64_ARRAY);
+    impl_field!(
+        NonZeroU8, NonZeroU16, NonZeroI8, NonZeroI16, NonZeroI32 => Field::INT32, Field::INT32_ARRAY
+    );
+    impl_field!(
+        NonZeroU32, NonZeroU64, NonZeroUsize, NonZeroI64, NonZeroIsize
+        => Field::INT64, Field::INT64_ARRAY
+    );
     impl_field!(f32, f64 => Field::FLOAT, Field::FLOAT_ARRAY);
-    impl_field!(String => Field::STRING, Field::STRING_ARRAY);
+    impl_field!(String, Arc<str>, Cow<'_, str> => Field::STRING, Field::STRING_ARRAY);
     impl_field!(bool => Field::BOOL, Field::BOOL_ARRAY);
     impl_field!(serde_json::Value => Field::OBJECT, Field::OBJECT_ARRAY);
+    impl_field!(GeoPoint => Field::GEOPOINT, Field::GEOPOINT_ARRAY);
+
+    // timestamps are mostly stored to be sorted by
+    impl<P> TypesenseField for Timestamp<P> {
+        const TYPE: &'static str = Field::INT64;
+        const SORT: Option<bool> = Some(true);
+    }
+
+    impl<P> TypesenseField for Vec<Timestamp<P>> {
+        const TYPE: &'static str = Field::INT64_ARRAY;
+    }
+
+    #[cfg(feature = "uuid")]
+    impl_field!(uuid::Uuid => Field::STRING, Field::STRING_ARRAY);
+    #[cfg(feature = "url")]
+    impl_field!(url::Url => Field::STRING, Field::STRING_ARRAY);
+    #[cfg(feature = "rust_decimal")]
+    impl_field!(rust_decimal::Decimal => Field::STRING, Field::STRING_ARRAY);
+    #[cfg(feature = "smol_str")]
+    impl_field!(smol_str::SmolStr => Field::STRING, Field::STRING_ARRAY);
+
+    // sets and small vecs are stored as arrays
+    impl<T> TypesenseField for HashSet<T>
+    where
+        Vec<T>: TypesenseField,
+    {
+        const TYPE: &'static str = <Vec<T>>::TYPE;
+    }
+
+    impl<T> TypesenseField for BTreeSet<T>
+    where
+        Vec<T>: TypesenseField,
+    {
+        const TYPE: &'static str = <Vec<T>>::TYPE;
+    }
+
+    #[cfg(feature = "smallvec")]
+    impl<A: smallvec::Array> TypesenseField for smallvec::SmallVec<A>
+    where
+        Vec<A::Item>: TypesenseField,
+    {
+        const TYPE: &'static str = <Vec<A::Item>>::TYPE;
+    }
 
-    impl<'a> TypesenseField for &'a str {
+    impl TypesenseField for &str {
         const TYPE: &'static str = Field::STRING;
     }
 
-    impl<'a> TypesenseField for Vec<&'a str> {
+    impl TypesenseField for Vec<&str> {
         const TYPE: &'static str = Field::STRING_ARRAY;
     }
 }
@@ -84,9 +192,7 @@ mod error {
     #[visibility(pub(crate))]
     pub enum Error {
         #[error("Request failed to Typesense")]
-        ActionFailed(#[source] reqwest::Error),
-        #[error("Failed to deserialize response body as json")]
-        DeserializeBody(#[source] reqwest::Error),
+        ActionFailed(#[source] Box<dyn std::error::Error + Send + Sync>),
         #[error("Failed to deserialize text {text} as json")]
         DeserializeText {
             text: String,
@@ -94,6 +200,10 @@ mod error {
         },
         #[error("Failed to parse response as either `message` or `{0}`")]
         ParseFailed(&'static str),
+        #[error("Failed to serialize request body to json")]
+        BodyToJson(#[source] serde_json::Error),
+        #[error("Failed to serialize query parameters")]
+        QueryToUrl(#[source] serde_urlencoded::ser::Error),
         #[error("Failed to serialize document {document:?} to json")]
         DocumentToJson {
             document: String,
@@ -108,6 +218,8 @@ mod error {
         TypesenseError(String),
         #[error("API Key not found")]
         ApiKeyNotFound,
+        #[error("Document has no id")]
+        DocumentIdNotFound,
         #[error("Hostname not found")]
         HostnameNotFound,
         #[error("API Key ({api_key}) is invalid")]
@@ -117,5 +229,17 @@ mod error {
         },
         #[error("ReqwestBuilder failed to build")]
         ReqwestBuilderFailed(#[source] reqwest::Error),
+        #[error("Scoped search key is invalid: {0}")]
+        InvalidScopedSearchKey(&'static str),
+        #[error("API Key must be at least 4 characters to generate a scoped search key")]
+        ScopedSearchKeyTooShort,
+        #[error("API Key prefix ({0}) must be ascii to generate a scoped search key")]
+        NonAsciiKeyPrefix(String),
+        #[error("Scoped search key `expires_at` must be seconds since unix epoch, got {0}")]
+        InvalidScopedSearchExpiry(serde_json::Value),
+        #[error("Failed to serialize scoped search key parameters to json")]
+        ScopedSearchParamsToJson(#[source] serde_json::Error),
+        #[error("API Key cannot be used as an HMAC key")]
+        InvalidHmacKey(#[source] hmac::digest::InvalidLength),
     }
 }
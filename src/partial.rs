@@ -1,11 +1,39 @@
+use crate::timestamp::Timestamp;
 use __private::SerdeImpl;
-use std::collections::HashMap;
+use std::{
+    borrow::Cow,
+    collections::{BTreeSet, HashMap, HashSet},
+    fmt,
+    hash::Hash,
+    num::*,
+    sync::Arc,
+    time::SystemTime,
+};
 
+/// Type with a partial representation, used to update documents.
+///
+/// Values and arrays, including arrays of objects, are replaced wholesale by merges and
+/// applies; derived documents and their nested objects merge field by field, and optional
+/// fields follow their [`Patch`].
 pub trait Partial: SerdeImpl {
     type Partial: SerdeImpl;
 
     fn into_partial(self) -> Self::Partial;
     fn from_partial(partial: Self::Partial) -> Result<Self, TryFromPartialError>;
+
+    /// Merges `other` into `partial`, as if `other` was sent after it.
+    fn merge_partial(partial: &mut Self::Partial, other: Self::Partial) {
+        *partial = other;
+    }
+
+    /// Updates `self` with `partial`, as typesense would.
+    ///
+    /// Fails if a missing nested object or variant is set by an incomplete partial, in which
+    /// case the fields before it are already updated; apply to a clone to keep the original.
+    fn apply_partial(&mut self, partial: Self::Partial) -> Result<(), TryFromPartialError> {
+        *self = Self::from_partial(partial)?;
+        Ok(())
+    }
 }
 
 mod __private {
@@ -21,20 +49,114 @@ mod __private {
     }
 }
 
+/// Update of an optional field: left alone, cleared with `null`, or set.
+///
+/// Skip [`Patch::Unchanged`] fields when serializing with
+/// `#[serde(default, skip_serializing_if = "Patch::is_unchanged")]`, as generated partials do.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Patch<T> {
+    #[default]
+    Unchanged,
+    Null,
+    Value(T),
+}
+
+impl<T> Patch<T> {
+    pub fn is_unchanged(&self) -> bool {
+        matches!(self, Self::Unchanged)
+    }
+
+    pub fn is_null(&self) -> bool {
+        matches!(self, Self::Null)
+    }
+
+    /// The value, if set.
+    pub fn value(&self) -> Option<&T> {
+        match self {
+            Self::Value(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> Patch<U> {
+        match self {
+            Self::Unchanged => Patch::Unchanged,
+            Self::Null => Patch::Null,
+            Self::Value(value) => Patch::Value(f(value)),
+        }
+    }
+}
+
+/// `None` clears the field.
+impl<T> From<Option<T>> for Patch<T> {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Self::Null, Self::Value)
+    }
+}
+
+/// Fails on [`Patch::Unchanged`], which must be skipped: sent as `null` it would clear the field.
+impl<T: serde::Serialize> serde::Serialize for Patch<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::Value(value) => serializer.serialize_some(value),
+            Self::Null => serializer.serialize_none(),
+            Self::Unchanged => Err(unchanged_error()),
+        }
+    }
+}
+
+#[doc(hidden)]
+pub fn unchanged_error<E: serde::ser::Error>() -> E {
+    E::custom(
+        "unchanged patches must be skipped, with `skip_serializing_if = \"Patch::is_unchanged\"`",
+    )
+}
+
+// missing fields are only `Unchanged` with `#[serde(default)]`
+impl<'de, T: serde::Deserialize<'de>> serde::Deserialize<'de> for Patch<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Option::deserialize(deserializer).map(Self::from)
+    }
+}
+
+/// Optional fields of a partial are [`Patch`]es, so updates can clear them.
 impl<T: Partial> Partial for Option<T>
 where
     T: serde::Serialize,
     for<'de> T: serde::Deserialize<'de>,
     for<'de> T::Partial: serde::Deserialize<'de>,
 {
-    type Partial = Option<T::Partial>;
+    type Partial = Patch<T::Partial>;
 
+    // a document without the value clears it
     fn into_partial(self) -> Self::Partial {
-        self.map(|t| t.into_partial())
+        self.map(T::into_partial).into()
     }
 
     fn from_partial(partial: Self::Partial) -> Result<Self, TryFromPartialError> {
-        partial.map(T::from_partial).transpose()
+        match partial {
+            Patch::Value(value) => T::from_partial(value).map(Some),
+            Patch::Unchanged | Patch::Null => Ok(None),
+        }
+    }
+
+    fn merge_partial(partial: &mut Self::Partial, other: Self::Partial) {
+        match (partial, other) {
+            (_, Patch::Unchanged) => {}
+            (Patch::Value(partial), Patch::Value(other)) => T::merge_partial(partial, other),
+            (partial, other) => *partial = other,
+        }
+    }
+
+    fn apply_partial(&mut self, partial: Self::Partial) -> Result<(), TryFromPartialError> {
+        match (self, partial) {
+            (_, Patch::Unchanged) => {}
+            (this, Patch::Null) => *this = None,
+            (Some(value), Patch::Value(partial)) => value.apply_partial(partial)?,
+            (this, Patch::Value(partial)) => *this = Some(T::from_partial(partial)?),
+        }
+
+        Ok(())
     }
 }
 
@@ -74,6 +196,70 @@ impl<K: SerdeImpl + std::cmp::Eq + std::hash::Hash, V: SerdeImpl> Partial for Ha
     }
 }
 
+impl<T: SerdeImpl + Eq + Hash> Partial for HashSet<T> {
+    type Partial = Self;
+
+    fn into_partial(self) -> Self::Partial {
+        self
+    }
+
+    fn from_partial(partial: Self::Partial) -> Result<Self, TryFromPartialError> {
+        Ok(partial)
+    }
+}
+
+impl<T: SerdeImpl + Ord> Partial for BTreeSet<T> {
+    type Partial = Self;
+
+    fn into_partial(self) -> Self::Partial {
+        self
+    }
+
+    fn from_partial(partial: Self::Partial) -> Result<Self, TryFromPartialError> {
+        Ok(partial)
+    }
+}
+
+#[cfg(feature = "smallvec")]
+impl<A: smallvec::Array> Partial for smallvec::SmallVec<A>
+where
+    A::Item: SerdeImpl,
+{
+    type Partial = Self;
+
+    fn into_partial(self) -> Self::Partial {
+        self
+    }
+
+    fn from_partial(partial: Self::Partial) -> Result<Self, TryFromPartialError> {
+        Ok(partial)
+    }
+}
+
+impl<P: fmt::Debug> Partial for Timestamp<P> {
+    type Partial = Self;
+
+    fn into_partial(self) -> Self::Partial {
+        self
+    }
+
+    fn from_partial(partial: Self::Partial) -> Result<Self, TryFromPartialError> {
+        Ok(partial)
+    }
+}
+
+impl<'a> Partial for Cow<'a, str> {
+    type Partial = Self;
+
+    fn into_partial(self) -> Self::Partial {
+        self
+    }
+
+    fn from_partial(partial: Self::Partial) -> Result<Self, TryFromPartialError> {
+        Ok(partial)
+    }
+}
+
 impl<'a> Partial for &'a str
 where
     for<'de> &'a str: serde::Deserialize<'de>,
@@ -121,9 +307,35 @@ impl_partial!(
     f32,
     f64,
     String,
-    serde_json::Value
+    serde_json::Value,
+    crate::geo::GeoPoint,
+    Arc<str>,
+    SystemTime,
+    NonZeroU8,
+    NonZeroU16,
+    NonZeroU32,
+    NonZeroU64,
+    NonZeroUsize,
+    NonZeroI8,
+    NonZeroI16,
+    NonZeroI32,
+    NonZeroI64,
+    NonZeroIsize
 );
 
+#[cfg(feature = "chrono")]
+impl_partial!(chrono::DateTime<chrono::Utc>, chrono::NaiveDate);
+#[cfg(feature = "time")]
+impl_partial!(time::OffsetDateTime);
+#[cfg(feature = "uuid")]
+impl_partial!(uuid::Uuid);
+#[cfg(feature = "url")]
+impl_partial!(url::Url);
+#[cfg(feature = "rust_decimal")]
+impl_partial!(rust_decimal::Decimal);
+#[cfg(feature = "smol_str")]
+impl_partial!(smol_str::SmolStr);
+
 use thiserror::Error;
 use tosserror::Toss;
 
@@ -133,3 +345,32 @@ pub struct TryFromPartialError {
     pub type_name: &'static str,
     pub field: &'static str,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_patch_serialize() {
+        assert_eq!(serde_json::to_value(Patch::Value(1)).unwrap(), json!(1));
+        assert_eq!(
+            serde_json::to_value(Patch::<i32>::Null).unwrap(),
+            json!(null)
+        );
+
+        // leaving a field alone must not look like clearing it
+        let err = serde_json::to_value(Patch::<i32>::Unchanged).unwrap_err();
+        assert!(err.to_string().contains("is_unchanged"));
+        assert!(serde_json::to_value(vec![Patch::Value(1), Patch::Unchanged]).is_err());
+
+        #[derive(serde::Serialize)]
+        struct Handwritten {
+            label: Patch<String>,
+        }
+        let partial = Handwritten {
+            label: Patch::Unchanged,
+        };
+        assert!(serde_json::to_value(partial).is_err());
+    }
+}
@@ -12,6 +12,15 @@ pub struct CollectionSchema<'a> {
     pub default_sorting_field: Option<&'a str>,
     pub enable_nested_fields: bool,
     pub symbols_to_index: Option<Vec<String>>,
+    pub token_separators: Option<Vec<String>>,
+    pub metadata: Option<serde_json::Value>,
+    pub voice_query_model: Option<VoiceQueryModel>,
+}
+
+/// Model used to transcribe voice queries, i.e. `ts/whisper/base.en`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VoiceQueryModel {
+    pub model_name: String,
 }
 
 impl<'a> CollectionSchema<'a> {
@@ -22,6 +31,9 @@ impl<'a> CollectionSchema<'a> {
             default_sorting_field: None,
             enable_nested_fields: false,
             symbols_to_index: None,
+            token_separators: None,
+            metadata: None,
+            voice_query_model: None,
         }
     }
 
@@ -77,11 +89,55 @@ impl<'a> CollectionSchema<'a> {
         );
         self
     }
+
+    pub fn token_separators<T: AsRef<str>>(
+        mut self,
+        token_separators: impl IntoIterator<Item = T>,
+    ) -> Self {
+        self.token_separators.replace(
+            token_separators
+                .into_iter()
+                .map(|t| t.as_ref().to_owned())
+                .collect(),
+        );
+        self
+    }
+
+    /// Arbitrary json object stored alongside the collection.
+    pub fn metadata(mut self, metadata: serde_json::Value) -> Self {
+        self.metadata.replace(metadata);
+        self
+    }
+
+    /// Sets a single key of the metadata object, replacing metadata that is not an object.
+    pub fn metadata_entry(
+        mut self,
+        key: impl ToString,
+        value: impl Into<serde_json::Value>,
+    ) -> Self {
+        let metadata = self
+            .metadata
+            .get_or_insert_with(|| serde_json::Value::Object(Default::default()));
+
+        if !metadata.is_object() {
+            *metadata = serde_json::Value::Object(Default::default());
+        }
+
+        metadata[key.to_string()] = value.into();
+        self
+    }
+
+    pub fn voice_query_model(mut self, model_name: impl ToString) -> Self {
+        self.voice_query_model.replace(VoiceQueryModel {
+            model_name: model_name.to_string(),
+        });
+        self
+    }
 }
 
 #[borrowme]
 #[skip_serializing_none]
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Field<'a> {
     #[serde(rename = "type")]
     pub field_type: &'a str,
@@ -91,6 +147,105 @@ pub struct Field<'a> {
     pub sort: Option<bool>,
     pub optional: Option<bool>,
     pub drop: Option<bool>,
+    pub locale: Option<Cow<'a, str>>,
+    pub infix: Option<bool>,
+    pub stem: Option<bool>,
+    pub range_index: Option<bool>,
+    pub store: Option<bool>,
+    /// field of another collection this field joins on, i.e. `customers.id`
+    pub reference: Option<Cow<'a, str>>,
+    pub token_separators: Option<Vec<Cow<'a, str>>>,
+    pub symbols_to_index: Option<Vec<Cow<'a, str>>>,
+    /// number of dimensions of a vector field; only valid for `float[]` fields
+    pub num_dim: Option<usize>,
+    #[copy]
+    pub vec_dist: Option<VectorDistance>,
+    #[copy]
+    pub hnsw_params: Option<HnswParams>,
+    /// auto-embedding config; typesense generates the vector from the `from` fields
+    pub embed: Option<FieldEmbed<'a>>,
+}
+
+/// Distance metric used for nearest neighbor search on a vector field.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum VectorDistance {
+    Cosine,
+    Ip,
+}
+
+#[skip_serializing_none]
+#[derive(
+    Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
+pub struct HnswParams {
+    pub ef_construction: Option<usize>,
+    #[serde(rename = "M")]
+    pub m: Option<usize>,
+}
+
+#[borrowme]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+pub struct FieldEmbed<'a> {
+    pub from: Vec<Cow<'a, str>>,
+    pub model_config: ModelConfig<'a>,
+}
+
+#[borrowme]
+#[skip_serializing_none]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ModelConfig<'a> {
+    pub model_name: Cow<'a, str>,
+    pub api_key: Option<Cow<'a, str>>,
+    pub url: Option<Cow<'a, str>>,
+    pub access_token: Option<Cow<'a, str>>,
+    pub refresh_token: Option<Cow<'a, str>>,
+    pub client_id: Option<Cow<'a, str>>,
+    pub client_secret: Option<Cow<'a, str>>,
+    pub project_id: Option<Cow<'a, str>>,
+    pub indexing_prefix: Option<Cow<'a, str>>,
+    pub query_prefix: Option<Cow<'a, str>>,
+}
+
+impl<'a> FieldEmbed<'a> {
+    pub fn new<T: Into<Cow<'a, str>>>(
+        from: impl IntoIterator<Item = T>,
+        model_config: ModelConfig<'a>,
+    ) -> Self {
+        Self {
+            from: from.into_iter().map(Into::into).collect(),
+            model_config,
+        }
+    }
+}
+
+impl<'a> ModelConfig<'a> {
+    pub fn new(model_name: impl Into<Cow<'a, str>>) -> Self {
+        Self {
+            model_name: model_name.into(),
+            ..Default::default()
+        }
+    }
+
+    pub fn api_key(mut self, api_key: impl Into<Cow<'a, str>>) -> Self {
+        self.api_key.replace(api_key.into());
+        self
+    }
+
+    pub fn url(mut self, url: impl Into<Cow<'a, str>>) -> Self {
+        self.url.replace(url.into());
+        self
+    }
+
+    pub fn indexing_prefix(mut self, indexing_prefix: impl Into<Cow<'a, str>>) -> Self {
+        self.indexing_prefix.replace(indexing_prefix.into());
+        self
+    }
+
+    pub fn query_prefix(mut self, query_prefix: impl Into<Cow<'a, str>>) -> Self {
+        self.query_prefix.replace(query_prefix.into());
+        self
+    }
 }
 
 impl<'a> Field<'a> {
@@ -100,7 +255,7 @@ impl<'a> Field<'a> {
 }
 
 impl OwnedField {
-    pub fn borrow(&self) -> Field {
+    pub fn borrow(&self) -> Field<'_> {
         borrowme::borrow(self)
     }
 }
@@ -130,6 +285,74 @@ impl<'a> Field<'a> {
         self.drop.replace(should_drop);
         self
     }
+
+    pub fn infix(mut self, infix: bool) -> Self {
+        self.infix.replace(infix);
+        self
+    }
+
+    pub fn stem(mut self, stem: bool) -> Self {
+        self.stem.replace(stem);
+        self
+    }
+
+    pub fn range_index(mut self, range_index: bool) -> Self {
+        self.range_index.replace(range_index);
+        self
+    }
+
+    pub fn store(mut self, store: bool) -> Self {
+        self.store.replace(store);
+        self
+    }
+
+    pub fn locale(mut self, locale: impl Into<Cow<'a, str>>) -> Self {
+        self.locale.replace(locale.into());
+        self
+    }
+
+    pub fn reference(mut self, reference: impl Into<Cow<'a, str>>) -> Self {
+        self.reference.replace(reference.into());
+        self
+    }
+
+    pub fn token_separators<T: Into<Cow<'a, str>>>(
+        mut self,
+        token_separators: impl IntoIterator<Item = T>,
+    ) -> Self {
+        self.token_separators
+            .replace(token_separators.into_iter().map(Into::into).collect());
+        self
+    }
+
+    pub fn symbols_to_index<T: Into<Cow<'a, str>>>(
+        mut self,
+        symbols_to_index: impl IntoIterator<Item = T>,
+    ) -> Self {
+        self.symbols_to_index
+            .replace(symbols_to_index.into_iter().map(Into::into).collect());
+        self
+    }
+
+    pub fn num_dim(mut self, num_dim: usize) -> Self {
+        self.num_dim.replace(num_dim);
+        self
+    }
+
+    pub fn vec_dist(mut self, vec_dist: VectorDistance) -> Self {
+        self.vec_dist.replace(vec_dist);
+        self
+    }
+
+    pub fn hnsw_params(mut self, hnsw_params: HnswParams) -> Self {
+        self.hnsw_params.replace(hnsw_params);
+        self
+    }
+
+    pub fn embed(mut self, embed: FieldEmbed<'a>) -> Self {
+        self.embed.replace(embed);
+        self
+    }
 }
 
 impl OwnedField {
@@ -157,6 +380,82 @@ impl OwnedField {
         self.drop.replace(should_drop);
         self
     }
+
+    pub fn infix(mut self, infix: bool) -> Self {
+        self.infix.replace(infix);
+        self
+    }
+
+    pub fn stem(mut self, stem: bool) -> Self {
+        self.stem.replace(stem);
+        self
+    }
+
+    pub fn range_index(mut self, range_index: bool) -> Self {
+        self.range_index.replace(range_index);
+        self
+    }
+
+    pub fn store(mut self, store: bool) -> Self {
+        self.store.replace(store);
+        self
+    }
+
+    pub fn locale(mut self, locale: impl ToString) -> Self {
+        self.locale.replace(Cow::Owned(locale.to_string()));
+        self
+    }
+
+    pub fn reference(mut self, reference: impl ToString) -> Self {
+        self.reference.replace(Cow::Owned(reference.to_string()));
+        self
+    }
+
+    pub fn token_separators<T: ToString>(
+        mut self,
+        token_separators: impl IntoIterator<Item = T>,
+    ) -> Self {
+        self.token_separators.replace(
+            token_separators
+                .into_iter()
+                .map(|t| Cow::Owned(t.to_string()))
+                .collect(),
+        );
+        self
+    }
+
+    pub fn symbols_to_index<T: ToString>(
+        mut self,
+        symbols_to_index: impl IntoIterator<Item = T>,
+    ) -> Self {
+        self.symbols_to_index.replace(
+            symbols_to_index
+                .into_iter()
+                .map(|t| Cow::Owned(t.to_string()))
+                .collect(),
+        );
+        self
+    }
+
+    pub fn num_dim(mut self, num_dim: usize) -> Self {
+        self.num_dim.replace(num_dim);
+        self
+    }
+
+    pub fn vec_dist(mut self, vec_dist: VectorDistance) -> Self {
+        self.vec_dist.replace(vec_dist);
+        self
+    }
+
+    pub fn hnsw_params(mut self, hnsw_params: HnswParams) -> Self {
+        self.hnsw_params.replace(hnsw_params);
+        self
+    }
+
+    pub fn embed(mut self, embed: OwnedFieldEmbed) -> Self {
+        self.embed.replace(embed);
+        self
+    }
 }
 
 macro_rules! field_init_impl {
@@ -177,11 +476,7 @@ macro_rules! field_init_impl {
                         Self {
                             field_type: Field:: [< $t:upper >],
                             name: Cow::Borrowed(name),
-                            facet: None,
-                            index: None,
-                            sort: None,
-                            optional: None,
-                            drop: None,
+                            ..Default::default()
                         }
                     }
 
@@ -190,11 +485,7 @@ macro_rules! field_init_impl {
                             Self {
                                 field_type: Field:: [< $t:upper _ARRAY >],
                                 name: Cow::Borrowed(name),
-                                facet: None,
-                                index: None,
-                                sort: None,
-                                optional: None,
-                                drop: None,
+                                ..Default::default()
                             }
                         }
                     )?
@@ -209,11 +500,7 @@ macro_rules! field_init_impl {
                         Self {
                             field_type: Field:: [< $t:upper >] .to_owned(),
                             name: Cow::Owned(name),
-                            facet: None,
-                            index: None,
-                            sort: None,
-                            optional: None,
-                            drop: None,
+                            ..Default::default()
                         }
                     }
 
@@ -222,11 +509,7 @@ macro_rules! field_init_impl {
                             Self {
                                 field_type: Field:: [< $t:upper _ARRAY >] .to_owned(),
                                 name: Cow::Owned(name),
-                                facet: None,
-                                index: None,
-                                sort: None,
-                                optional: None,
-                                drop: None,
+                                ..Default::default()
                             }
                         }
                     )?
@@ -269,4 +552,31 @@ mod tests {
             r#"{"type":"int32","name":"field1","facet":true}"#
         );
     }
+
+    #[test]
+    fn test_vector_field_serde() {
+        let field = Field::float_array("embedding")
+            .num_dim(384)
+            .vec_dist(VectorDistance::Cosine)
+            .hnsw_params(HnswParams {
+                ef_construction: Some(200),
+                m: Some(16),
+            });
+        assert_eq!(
+            serde_json::to_string(&field).unwrap(),
+            r#"{"type":"float[]","name":"embedding","num_dim":384,"vec_dist":"cosine","hnsw_params":{"ef_construction":200,"M":16}}"#
+        );
+
+        let field = Field::float_array("embedding").embed(FieldEmbed::new(
+            ["title", "description"],
+            ModelConfig::new("ts/all-MiniLM-L12-v2"),
+        ));
+        assert_eq!(
+            serde_json::to_string(&field).unwrap(),
+            r#"{"type":"float[]","name":"embedding","embed":{"from":["title","description"],"model_config":{"model_name":"ts/all-MiniLM-L12-v2"}}}"#
+        );
+
+        let owned = field.to_owned();
+        assert_eq!(owned.borrow(), field);
+    }
 }
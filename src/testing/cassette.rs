@@ -0,0 +1,381 @@
+use super::{json_response, path_and_query};
+use crate::{client::builder::TYPESENSE_API_KEY_QUERY_NAME, Request, Reqwest, Response, Transport};
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use serde_with::skip_serializing_none;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    env,
+    error::Error as StdError,
+    fmt::{self, Write},
+    fs, io,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+use thiserror::Error;
+
+/// Set to record cassettes again even if they already exist.
+pub const RECORD_ENV: &str = "TYPESENSE_RECORD";
+
+/// Transport recording request/response pairs to a json file, or replaying them from it.
+///
+/// Requests are replayed in the order they were recorded, and must match the recording's
+/// method, path, query and body; api keys are never recorded.
+///
+/// ```ignore
+/// let cassette = Cassette::auto("tests/cassettes/search.json", Reqwest::new())?;
+/// let client = Client::builder().build_with_transport(cassette)?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct Cassette<H = Reqwest> {
+    // only set when recording
+    transport: Option<H>,
+    tape: Arc<Tape>,
+}
+
+#[derive(Debug)]
+struct Tape {
+    path: PathBuf,
+    recording: bool,
+    interactions: Mutex<Vec<Interaction>>,
+    position: Mutex<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Interaction {
+    pub request: RecordedRequest,
+    pub response: RecordedResponse,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedRequest {
+    pub method: String,
+    pub path: String,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub query: BTreeMap<String, String>,
+    pub body: Option<Body>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedResponse {
+    pub status: u16,
+    pub body: Option<Body>,
+}
+
+/// Json bodies are stored as json so cassettes stay readable and diffable.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Body {
+    Json(Value),
+    Text(String),
+}
+
+#[derive(Debug, Error)]
+pub enum CassetteError {
+    #[error("Failed to read or write cassette {path:?}")]
+    Io { path: PathBuf, source: io::Error },
+    #[error("Failed to parse cassette {path:?}")]
+    Parse {
+        path: PathBuf,
+        source: serde_json::Error,
+    },
+    #[error("Request #{index} was not recorded in the cassette: {request:?}")]
+    Exhausted {
+        index: usize,
+        request: RecordedRequest,
+    },
+    #[error("Request #{index} does not match the cassette:\n{diff}")]
+    Mismatch { index: usize, diff: String },
+    #[error("Transport failed while recording")]
+    Transport(#[source] Box<dyn StdError + Send + Sync>),
+}
+
+impl Cassette {
+    /// Replay the cassette at `path`, without sending any request.
+    pub fn replay(path: impl AsRef<Path>) -> Result<Self, CassetteError> {
+        Ok(Self {
+            transport: None,
+            tape: Arc::new(Tape::load(path.as_ref())?),
+        })
+    }
+}
+
+impl<H: Transport> Cassette<H> {
+    /// Send requests through `transport`, recording them to `path` when the last clone is dropped
+    /// or [`Cassette::save`] is called.
+    pub fn record(path: impl AsRef<Path>, transport: H) -> Self {
+        Self {
+            transport: Some(transport),
+            tape: Arc::new(Tape {
+                path: path.as_ref().to_owned(),
+                recording: true,
+                interactions: Mutex::default(),
+                position: Mutex::default(),
+            }),
+        }
+    }
+
+    /// Replay the cassette at `path` if it exists, otherwise record it with `transport`.
+    ///
+    /// Set the `TYPESENSE_RECORD` env var to record it again.
+    pub fn auto(path: impl AsRef<Path>, transport: H) -> Result<Self, CassetteError> {
+        let path = path.as_ref();
+
+        if env::var_os(RECORD_ENV).is_some() || !path.exists() {
+            return Ok(Self::record(path, transport));
+        }
+
+        Ok(Self {
+            transport: None,
+            tape: Arc::new(Tape::load(path)?),
+        })
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.tape.recording
+    }
+
+    /// Write the recorded interactions to the cassette file.
+    pub fn save(&self) -> Result<(), CassetteError> {
+        self.tape.save()
+    }
+
+    async fn send(&self, request: Request) -> Result<Response, CassetteError> {
+        let recorded = RecordedRequest::from(&request);
+
+        let Some(transport) = self.transport.as_ref() else {
+            return self.tape.replay(recorded);
+        };
+
+        let res = transport
+            .send(request)
+            .await
+            .map_err(|e| CassetteError::Transport(Box::new(e)))?;
+
+        lock(&self.tape.interactions).push(Interaction {
+            request: recorded,
+            response: RecordedResponse {
+                status: res.status.as_u16(),
+                body: Body::from_bytes(&res.body),
+            },
+        });
+
+        Ok(res)
+    }
+}
+
+impl<H: Transport> Transport for Cassette<H> {
+    type Error = CassetteError;
+
+    async fn send(&self, request: Request) -> Result<Response, Self::Error> {
+        Cassette::send(self, request).await
+    }
+}
+
+impl Tape {
+    fn load(path: &Path) -> Result<Self, CassetteError> {
+        let file = fs::read(path).map_err(|source| CassetteError::Io {
+            path: path.to_owned(),
+            source,
+        })?;
+        let interactions =
+            serde_json::from_slice(&file).map_err(|source| CassetteError::Parse {
+                path: path.to_owned(),
+                source,
+            })?;
+
+        Ok(Self {
+            path: path.to_owned(),
+            recording: false,
+            interactions: Mutex::new(interactions),
+            position: Mutex::default(),
+        })
+    }
+
+    fn save(&self) -> Result<(), CassetteError> {
+        let io_err = |source| CassetteError::Io {
+            path: self.path.clone(),
+            source,
+        };
+
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir).map_err(io_err)?;
+        }
+
+        let json = serde_json::to_vec_pretty(&*lock(&self.interactions))
+            .expect("interactions serialize to json");
+        fs::write(&self.path, json).map_err(io_err)
+    }
+
+    fn replay(&self, request: RecordedRequest) -> Result<Response, CassetteError> {
+        let mut position = lock(&self.position);
+        let index = *position;
+
+        let interactions = lock(&self.interactions);
+        let Some(interaction) = interactions.get(index) else {
+            return Err(CassetteError::Exhausted { index, request });
+        };
+
+        let diff = interaction.request.diff(&request);
+        if !diff.is_empty() {
+            return Err(CassetteError::Mismatch { index, diff });
+        }
+        *position += 1;
+
+        let response = &interaction.response;
+        let body = response
+            .body
+            .as_ref()
+            .map(Body::to_bytes)
+            .unwrap_or_default();
+
+        Ok(json_response(response.status, body))
+    }
+}
+
+impl Drop for Tape {
+    fn drop(&mut self) {
+        if self.recording {
+            if let Err(e) = self.save() {
+                tracing::error!("failed to save cassette: {e}");
+            }
+        }
+    }
+}
+
+impl From<&Request> for RecordedRequest {
+    fn from(request: &Request) -> Self {
+        let (path, query) = path_and_query(&request.url)
+            .split_once('?')
+            .unwrap_or((path_and_query(&request.url), ""));
+
+        let query = serde_urlencoded::from_str::<Vec<(String, String)>>(query)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|(key, _)| key != TYPESENSE_API_KEY_QUERY_NAME)
+            .collect();
+
+        Self {
+            method: request.method.to_string(),
+            path: path.to_owned(),
+            query,
+            body: Body::from_bytes(&request.body),
+        }
+    }
+}
+
+impl RecordedRequest {
+    // one line per differing part, empty if the requests match
+    fn diff(&self, actual: &Self) -> String {
+        let mut diff = String::new();
+
+        let mut line = |name: &str, expected: &dyn fmt::Debug, actual: &dyn fmt::Debug| {
+            let _ = writeln!(
+                diff,
+                "  {name}:\n    expected: {expected:?}\n    actual:   {actual:?}"
+            );
+        };
+
+        if self.method != actual.method {
+            line("method", &self.method, &actual.method);
+        }
+        if self.path != actual.path {
+            line("path", &self.path, &actual.path);
+        }
+        let keys = self.query.keys().chain(actual.query.keys());
+        for key in keys.collect::<BTreeSet<_>>() {
+            let (expected, actual) = (self.query.get(key), actual.query.get(key));
+            if expected != actual {
+                line(&format!("query `{key}`"), &expected, &actual);
+            }
+        }
+        if self.body != actual.body {
+            line("body", &self.body, &actual.body);
+        }
+
+        diff
+    }
+}
+
+impl Body {
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.is_empty() {
+            return None;
+        }
+
+        Some(match serde_json::from_slice(bytes) {
+            Ok(json) => Self::Json(json),
+            Err(_) => Self::Text(String::from_utf8_lossy(bytes).into_owned()),
+        })
+    }
+
+    fn to_bytes(&self) -> Bytes {
+        match self {
+            Self::Json(json) => Bytes::from(json.to_string()),
+            Self::Text(text) => Bytes::from(text.clone()),
+        }
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{testing::MockServer, Client};
+
+    fn client<H: Transport>(transport: H) -> Client<H> {
+        Client::builder()
+            .hostname("http://localhost:8108")
+            .api_key("secret")
+            .build_with_transport(transport)
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_cassette_record_replay() {
+        let path = env::temp_dir().join(format!("typesensei-cassette-{}.json", std::process::id()));
+
+        let server = MockServer::new();
+        let recorder = Cassette::record(&path, server.clone());
+        let recording = client(recorder.clone());
+        recording.create_alias("books", "library").await.unwrap();
+        assert!(recording.create_alias("books", "").await.is_err());
+        let aliases = recording.retrieve_aliases().await.unwrap();
+        recorder.save().unwrap();
+        // the recording is saved again when its last clone is dropped
+        drop((recording, recorder));
+
+        let saved = fs::read_to_string(&path).unwrap();
+        assert!(!saved.contains("secret"));
+
+        let replaying = client(Cassette::replay(&path).unwrap());
+        replaying.create_alias("books", "library").await.unwrap();
+        assert!(replaying.create_alias("books", "").await.is_err());
+        let replayed = replaying.retrieve_aliases().await.unwrap();
+        assert_eq!(replayed.aliases.len(), aliases.aliases.len());
+
+        let exhausted = replaying.retrieve_aliases().await.unwrap_err();
+        assert!(exhausted.to_string().contains("Request failed"));
+
+        let replaying = client(Cassette::replay(&path).unwrap());
+        let res = replaying.create_alias("novels", "library").await;
+        let Err(crate::Error::ActionFailed(e)) = res else {
+            panic!("expected mismatch, got {res:?}");
+        };
+        let e = e.downcast::<CassetteError>().unwrap();
+        assert!(matches!(*e, CassetteError::Mismatch { index: 0, .. }));
+        assert!(e.to_string().contains(
+            r#"body:
+    expected: Some(Json(Object {"collection_name": String("books")}))
+    actual:   Some(Json(Object {"collection_name": String("novels")}))"#
+        ));
+
+        fs::remove_file(&path).unwrap();
+    }
+}
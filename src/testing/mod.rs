@@ -0,0 +1,373 @@
+//! In-memory fake of the typesense API for tests, enabled with the `testing` feature.
+//!
+//! [`MockServer`] implements the collections, documents, aliases and keys endpoints,
+//! and a simplified search:
+//! - `q` matches documents whose `query_by` fields contain it, case insensitively
+//! - `filter_by` supports clauses like `field:=value`, `field:>10` or `field:[a, b]` joined with `&&`
+//! - `sort_by` sorts on fields in the document
+//! - `page`, `per_page`, `offset` and `limit` paginate the hits
+//!
+//! API keys are not checked.
+//!
+//! It can be used in-process as a [`Transport`] with [`MockServer::client`],
+//! or bound to a local port with [`MockServer::start`].
+//!
+//! [`Cassette`] records requests sent to a real server into a json file,
+//! and replays them in later runs.
+
+use crate::{Client, Request, Response, Transport};
+use bytes::Bytes;
+use reqwest::header::{HeaderMap, HeaderValue, CONTENT_TYPE};
+use std::{
+    convert::Infallible,
+    future::Future,
+    sync::{Arc, Mutex},
+};
+
+mod cassette;
+mod search;
+mod server;
+mod state;
+pub use cassette::*;
+pub use server::*;
+use state::State;
+
+const HOSTNAME: &str = "http://typesense.mock";
+const API_KEY: &str = "mock";
+
+#[derive(Debug, Clone, Default)]
+pub struct MockServer {
+    state: Arc<Mutex<State>>,
+}
+
+impl MockServer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Client sending its requests to this server in-process.
+    pub fn client(&self) -> Client<MockServer> {
+        Client::builder()
+            .hostname(HOSTNAME)
+            .api_key(API_KEY)
+            .build_with_transport(self.clone())
+            .expect("hostname and api key are set")
+    }
+
+    /// Handle a request to `path_and_query`, i.e. `/collections/books/documents?action=upsert`.
+    pub fn handle(&self, method: &str, path_and_query: &str, body: &[u8]) -> (u16, Bytes) {
+        let (path, query) = path_and_query
+            .split_once('?')
+            .unwrap_or((path_and_query, ""));
+        let query = serde_urlencoded::from_str(query).unwrap_or_default();
+
+        // a panic in another thread only poisons the lock mid-test
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.handle(method, path, query, body)
+    }
+}
+
+impl Transport for MockServer {
+    type Error = Infallible;
+
+    fn send(&self, request: Request) -> impl Future<Output = Result<Response, Self::Error>> + Send {
+        let (status, body) = self.handle(
+            request.method.as_str(),
+            path_and_query(&request.url),
+            &request.body,
+        );
+
+        let res = json_response(status, body);
+
+        async move { Ok(res) }
+    }
+}
+
+// `http://localhost:8108/collections?a=b` -> `/collections?a=b`
+fn path_and_query(url: &str) -> &str {
+    url.split_once("://")
+        .and_then(|(_, rest)| rest.find('/').map(|i| &rest[i..]))
+        .unwrap_or("/")
+}
+
+fn json_response(status: u16, body: Bytes) -> Response {
+    let mut headers = HeaderMap::new();
+    headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+
+    Response {
+        status: status.try_into().unwrap_or_default(),
+        headers,
+        body,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{api::keys::Action, ApiKey, Filter, Partial, SearchQuery, Sort, Typesense};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Typesense)]
+    struct Book {
+        id: String,
+        title: String,
+        #[typesensei(facet = true)]
+        author: String,
+        year: i32,
+    }
+
+    fn book(id: &str, title: &str, author: &str, year: i32) -> Book {
+        Book {
+            id: id.to_owned(),
+            title: title.to_owned(),
+            author: author.to_owned(),
+            year,
+        }
+    }
+
+    async fn seed<H: Transport>(client: &Client<H>) {
+        client.collection::<Book>("books").create().await.unwrap();
+
+        let books = [
+            book("1", "The Hobbit", "Tolkien", 1937),
+            book("2", "The Silmarillion", "Tolkien", 1977),
+            book("3", "Dune", "Herbert", 1965),
+            book("4", "Children of Dune", "Herbert", 1976),
+        ];
+        let documents = client.documents::<Book>("books");
+        for book in &books {
+            documents.create(book).await.unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mock_documents() {
+        let server = MockServer::new();
+        let client = server.client();
+        seed(&client).await;
+
+        let collection = client.collection::<Book>("books").retreive().await.unwrap();
+        assert_eq!(collection.num_documents, 4);
+        assert!(client.collection::<Book>("books").create().await.is_err());
+
+        let documents = client.documents::<Book>("books");
+        assert_eq!(documents.retrieve(&"3".into()).await.unwrap().title, "Dune");
+
+        let mut partial = Book::partial();
+        partial.year.replace(1966);
+        let updated = documents.update(&"3".into(), &partial).await.unwrap();
+        assert_eq!(updated.year, Some(1966));
+        assert_eq!(documents.retrieve(&"3".into()).await.unwrap().year, 1966);
+
+        let dune = book("3", "Dune", "Frank Herbert", 1965);
+        assert_eq!(dune.id(), Some("3".to_owned()));
+        documents.update_document(&dune).await.unwrap();
+        let id = dune.id().unwrap();
+        assert_eq!(documents.retrieve(&id).await.unwrap(), dune);
+
+        documents.delete(id).await.unwrap();
+        assert!(documents.retrieve("3".to_owned()).await.is_err());
+
+        let partial = book("5", "Dune Messiah", "Herbert", 1969).into_partial();
+        documents.batch_upsert(&[partial]).await.unwrap();
+        assert_eq!(documents.retrieve(&"5".into()).await.unwrap().year, 1969);
+
+        let exported = documents.export().await.unwrap();
+        let ids = exported.iter().map(|b| b.id.as_str()).collect::<Vec<_>>();
+        assert_eq!(ids, ["1", "2", "4", "5"]);
+
+        client.create_alias("books", "library").await.unwrap();
+        let aliased = client.documents::<Book>("library");
+        assert_eq!(
+            aliased.retrieve(&"5".into()).await.unwrap().title,
+            "Dune Messiah"
+        );
+        assert_eq!(client.retrieve_aliases().await.unwrap().aliases.len(), 1);
+
+        client.delete_collection("books").await.unwrap();
+        assert!(client.retrieve_collections().await.unwrap().is_empty());
+        assert!(matches!(
+            documents.export().await,
+            Err(crate::Error::TypesenseError(_))
+        ));
+    }
+
+    #[derive(Debug, Serialize, Deserialize, Typesense)]
+    struct Note {
+        id: String,
+        text: String,
+        label: Option<String>,
+    }
+
+    #[tokio::test]
+    async fn test_mock_clear_field() {
+        let server = MockServer::new();
+        let client = server.client();
+        client.collection::<Note>("notes").create().await.unwrap();
+
+        let documents = client.documents::<Note>("notes");
+        let note = Note {
+            id: "1".to_owned(),
+            text: "milk".to_owned(),
+            label: Some("groceries".to_owned()),
+        };
+        documents.create(&note).await.unwrap();
+
+        // leaving the label out keeps it
+        let partial = Note::partial().with_text("oat milk".to_owned());
+        documents.update(&"1".into(), &partial).await.unwrap();
+        let note = documents.retrieve(&"1".into()).await.unwrap();
+        assert_eq!(note.label.as_deref(), Some("groceries"));
+
+        let mut partial = Note::partial();
+        partial.clear_label();
+        documents.update(&"1".into(), &partial).await.unwrap();
+        let note = documents.retrieve(&"1".into()).await.unwrap();
+        assert_eq!(note.text, "oat milk");
+        assert_eq!(note.label, None);
+    }
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Typesense)]
+    struct Address {
+        city: String,
+        street: Option<String>,
+    }
+
+    #[derive(Debug, Serialize, Deserialize, Typesense)]
+    struct Customer {
+        id: String,
+        #[typesensei(ty = "object")]
+        address: Address,
+    }
+
+    #[tokio::test]
+    async fn test_mock_nested_update() {
+        let server = MockServer::new();
+        let client = server.client();
+        client
+            .collection::<Customer>("customers")
+            .create()
+            .await
+            .unwrap();
+
+        let documents = client.documents::<Customer>("customers");
+        let mut customer = Customer {
+            id: "1".to_owned(),
+            address: Address {
+                city: "London".to_owned(),
+                street: Some("Baker St".to_owned()),
+            },
+        };
+        documents.create(&customer).await.unwrap();
+
+        // nested objects are updated field by field, like `apply_to` does
+        let partial =
+            Customer::partial().with_address(Address::partial().with_city("Leeds".to_owned()));
+        documents.update(&"1".into(), &partial).await.unwrap();
+        partial.apply_to(&mut customer).unwrap();
+        let stored = documents.retrieve(&"1".into()).await.unwrap();
+        assert_eq!(stored.address, customer.address);
+        assert_eq!(stored.address.street.as_deref(), Some("Baker St"));
+
+        let partial = Customer::partial().with_address(Address::partial().with_street(None));
+        documents.update(&"1".into(), &partial).await.unwrap();
+        let stored = documents.retrieve(&"1".into()).await.unwrap();
+        assert_eq!(stored.address.city, "Leeds");
+        assert_eq!(stored.address.street, None);
+    }
+
+    #[tokio::test]
+    async fn test_mock_search() {
+        let server = MockServer::new();
+        let client = server.client();
+        seed(&client).await;
+
+        let documents = client.documents::<Book>("books");
+
+        let res = documents
+            .search(&SearchQuery::new("dune").query_by(["title"]))
+            .await
+            .unwrap();
+        assert_eq!(res.found, 2);
+
+        let res = documents
+            .search(
+                &SearchQuery::new("*")
+                    .filter_by(Filter::raw("author:=Tolkien").and(Filter::raw("year:>1950")))
+                    .sort_by(Sort::desc("year")),
+            )
+            .await
+            .unwrap();
+        assert_eq!(res.found, 1);
+        assert_eq!(res.hits[0].document.id, "2");
+
+        let res = documents
+            .search(
+                &SearchQuery::new("*")
+                    .sort_by(Sort::asc("year"))
+                    .page(2)
+                    .per_page(3),
+            )
+            .await
+            .unwrap();
+        assert_eq!(res.found, 4);
+        assert_eq!(res.out_of, 4);
+        assert_eq!(res.hits.len(), 1);
+        assert_eq!(res.hits[0].document.year, 1977);
+
+        let res = documents
+            .multi_search(&[
+                SearchQuery::new("*").filter_by("year:[1937, 1965]"),
+                SearchQuery::new("hobbit").query_by(["title", "author"]),
+            ])
+            .await
+            .unwrap();
+        assert_eq!(res.results[0].found, 2);
+        assert_eq!(res.results[1].found, 1);
+
+        let res = documents
+            .search(&SearchQuery::new("*").filter_by("year:>1950 || year:<1940"))
+            .await;
+        assert!(res.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_mock_keys() {
+        let client = MockServer::new().client();
+        let keys = client.keys();
+
+        let key = keys.create(&ApiKey::search_only(["books"])).await.unwrap();
+        let id = key.id.unwrap();
+        assert!(key.value.is_some());
+
+        let res = keys.retreive(&id.to_string()).await.unwrap();
+        assert_eq!(res.actions, [Action::DocumentsSearch]);
+        assert_eq!(keys.retreive_all().await.unwrap().keys.len(), 1);
+
+        keys.delete(&id.to_string()).await.unwrap();
+        assert!(keys.retreive_all().await.unwrap().keys.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_mock_server() {
+        let server = MockServer::new();
+        let handle = server.start().await.unwrap();
+        let client = handle.client();
+        seed(&client).await;
+
+        let res = client
+            .documents::<Book>("books")
+            .search(&SearchQuery::new("silmarillion").query_by(["title"]))
+            .await
+            .unwrap();
+        assert_eq!(res.hits[0].document.id, "2");
+
+        // state is shared with the in-process client
+        let in_process = server.client();
+        let book = in_process
+            .documents::<Book>("books")
+            .retrieve(&"1".into())
+            .await;
+        assert_eq!(book.unwrap().title, "The Hobbit");
+    }
+}
@@ -0,0 +1,265 @@
+use serde_json::{json, Map, Value};
+use std::{cmp::Ordering, collections::HashMap};
+
+type Document = Map<String, Value>;
+
+#[derive(Debug)]
+pub(super) enum SearchError {
+    BadRequest(String),
+}
+
+fn bad_request(message: impl Into<String>) -> SearchError {
+    SearchError::BadRequest(message.into())
+}
+
+pub(super) fn search(
+    collection_name: &str,
+    documents: &[Document],
+    params: &HashMap<String, String>,
+) -> Result<Value, SearchError> {
+    let param = |name: &str| params.get(name).map(String::as_str);
+    let number = |name: &str| -> Result<Option<usize>, SearchError> {
+        param(name)
+            .map(|v| {
+                v.parse().map_err(|_| {
+                    bad_request(format!("Parameter `{name}` must be an unsigned integer."))
+                })
+            })
+            .transpose()
+    };
+
+    let q = param("q").unwrap_or("*");
+    let query_by = list(param("query_by").unwrap_or_default());
+    if q != "*" && query_by.is_empty() {
+        return Err(bad_request("Parameter `query_by` is required."));
+    }
+
+    let filters = param("filter_by")
+        .filter(|f| !f.trim().is_empty())
+        .map(parse_filter)
+        .transpose()?
+        .unwrap_or_default();
+    let sorts = param("sort_by").map(parse_sort).unwrap_or_default();
+
+    let lowercase = q.to_lowercase();
+    let mut hits = documents
+        .iter()
+        .filter(|doc| {
+            q == "*"
+                || query_by
+                    .iter()
+                    .any(|field| contains(doc.get(*field), &lowercase))
+        })
+        .filter(|doc| filters.iter().all(|f| f.matches(doc)))
+        .collect::<Vec<_>>();
+
+    // stable, so ties keep insertion order
+    hits.sort_by(|a, b| {
+        sorts
+            .iter()
+            .map(|(field, desc)| {
+                let ord = compare(a.get(*field), b.get(*field));
+                if *desc {
+                    ord.reverse()
+                } else {
+                    ord
+                }
+            })
+            .find(|ord| ord.is_ne())
+            .unwrap_or(Ordering::Equal)
+    });
+
+    let found = hits.len();
+    let per_page = number("limit")?.or(number("per_page")?).unwrap_or(10);
+    let page = number("page")?.unwrap_or(1).max(1);
+    let offset = number("offset")?.unwrap_or((page - 1) * per_page);
+
+    let include = param("include_fields").map(list);
+    let exclude = param("exclude_fields").map(list).unwrap_or_default();
+
+    let hits = hits
+        .into_iter()
+        .skip(offset)
+        .take(per_page)
+        .map(|doc| {
+            let document = doc
+                .iter()
+                .filter(|(k, _)| include.as_ref().is_none_or(|i| i.contains(&k.as_str())))
+                .filter(|(k, _)| !exclude.contains(&k.as_str()))
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect::<Document>();
+
+            json!({ "document": document, "highlights": [], "text_match": 0 })
+        })
+        .collect::<Vec<_>>();
+
+    Ok(json!({
+        "facet_counts": [],
+        "found": found,
+        "hits": hits,
+        "out_of": documents.len(),
+        "page": page,
+        "request_params": {
+            "collection_name": collection_name,
+            "per_page": per_page,
+            "q": q,
+        },
+        "search_cutoff": false,
+        "search_time_ms": 0,
+    }))
+}
+
+fn list(s: &str) -> Vec<&str> {
+    s.split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+fn contains(value: Option<&Value>, q: &str) -> bool {
+    match value {
+        Some(Value::String(s)) => s.to_lowercase().contains(q),
+        Some(Value::Array(values)) => values.iter().any(|v| contains(Some(v), q)),
+        _ => false,
+    }
+}
+
+fn compare(a: Option<&Value>, b: Option<&Value>) -> Ordering {
+    match (a, b) {
+        (Some(Value::Number(a)), Some(Value::Number(b))) => a
+            .as_f64()
+            .partial_cmp(&b.as_f64())
+            .unwrap_or(Ordering::Equal),
+        (Some(Value::String(a)), Some(Value::String(b))) => a.cmp(b),
+        (Some(Value::Bool(a)), Some(Value::Bool(b))) => a.cmp(b),
+        // documents missing the field go last
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        _ => Ordering::Equal,
+    }
+}
+
+fn parse_sort(sort_by: &str) -> Vec<(&str, bool)> {
+    list(sort_by)
+        .into_iter()
+        .map(|s| match s.rsplit_once(':') {
+            Some((field, order)) => (field.trim(), order.trim().eq_ignore_ascii_case("desc")),
+            None => (s, false),
+        })
+        .filter(|(field, _)| !field.starts_with('_'))
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Op {
+    Match,
+    Eq,
+    Ne,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+}
+
+#[derive(Debug)]
+struct Clause<'a> {
+    field: &'a str,
+    op: Op,
+    values: Vec<&'a str>,
+}
+
+fn parse_filter(filter_by: &str) -> Result<Vec<Clause<'_>>, SearchError> {
+    if filter_by.contains("||") || filter_by.contains('(') {
+        return Err(bad_request(format!(
+            "Mock server does not support filter `{filter_by}`, only clauses joined with `&&`."
+        )));
+    }
+
+    filter_by.split("&&").map(parse_clause).collect()
+}
+
+fn parse_clause(clause: &str) -> Result<Clause<'_>, SearchError> {
+    let Some((field, rest)) = clause.split_once(':') else {
+        return Err(bad_request(format!(
+            "Could not parse the filter query `{clause}`."
+        )));
+    };
+    let rest = rest.trim();
+
+    let (op, value) = [
+        (">=", Op::Ge),
+        ("<=", Op::Le),
+        ("!=", Op::Ne),
+        (">", Op::Gt),
+        ("<", Op::Lt),
+        ("=", Op::Eq),
+    ]
+    .into_iter()
+    .find_map(|(prefix, op)| rest.strip_prefix(prefix).map(|v| (op, v)))
+    .unwrap_or((Op::Match, rest));
+
+    let value = value.trim();
+    let values = match value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+        Some(values) => list(values),
+        None => vec![value],
+    };
+
+    let values = values.into_iter().map(|v| v.trim_matches('`')).collect();
+
+    Ok(Clause {
+        field: field.trim(),
+        op,
+        values,
+    })
+}
+
+impl Clause<'_> {
+    fn matches(&self, doc: &Document) -> bool {
+        // `!=` matches documents where none of the values are equal
+        let (op, negate) = match self.op {
+            Op::Ne => (Op::Eq, true),
+            op => (op, false),
+        };
+        let matches = |value: &Value| self.values.iter().any(|v| matches_value(op, value, v));
+
+        let res = match doc.get(self.field) {
+            Some(Value::Array(values)) => values.iter().any(matches),
+            Some(value) => matches(value),
+            None => false,
+        };
+
+        res != negate
+    }
+}
+
+fn matches_value(op: Op, value: &Value, filter: &str) -> bool {
+    match op {
+        Op::Eq => equals(value, filter),
+        Op::Ne => !equals(value, filter),
+        Op::Match => match value {
+            Value::String(s) => s.to_lowercase().contains(&filter.to_lowercase()),
+            value => equals(value, filter),
+        },
+        op => {
+            let (Some(value), Ok(filter)) = (value.as_f64(), filter.parse::<f64>()) else {
+                return false;
+            };
+
+            match op {
+                Op::Gt => value > filter,
+                Op::Ge => value >= filter,
+                Op::Lt => value < filter,
+                _ => value <= filter,
+            }
+        }
+    }
+}
+
+fn equals(value: &Value, filter: &str) -> bool {
+    match value {
+        Value::String(s) => s == filter,
+        Value::Number(n) => n.as_f64() == filter.parse().ok(),
+        Value::Bool(b) => filter.parse() == Ok(*b),
+        _ => false,
+    }
+}
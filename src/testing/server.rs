@@ -0,0 +1,122 @@
+use super::{MockServer, API_KEY};
+use crate::Client;
+use std::{io, net::SocketAddr};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+    net::{TcpListener, TcpStream},
+    task::JoinHandle,
+};
+
+/// [`MockServer`] bound to a local port; the server stops when this is dropped.
+#[derive(Debug)]
+pub struct MockServerHandle {
+    addr: SocketAddr,
+    task: JoinHandle<()>,
+}
+
+impl MockServerHandle {
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Url to use as the client's hostname, i.e. `http://127.0.0.1:53124`.
+    pub fn url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    /// Client sending its requests to this server over http.
+    pub fn client(&self) -> Client {
+        Client::new(&self.url(), API_KEY)
+    }
+}
+
+impl Drop for MockServerHandle {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+impl MockServer {
+    /// Serve this server on a random local port.
+    ///
+    /// Must be called within a tokio runtime.
+    pub async fn start(&self) -> io::Result<MockServerHandle> {
+        let listener = TcpListener::bind(("127.0.0.1", 0)).await?;
+        let addr = listener.local_addr()?;
+
+        let server = self.clone();
+        let task = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let server = server.clone();
+                tokio::spawn(async move {
+                    // a failed connection only fails the request made on it
+                    let _ = server.serve(stream).await;
+                });
+            }
+        });
+
+        Ok(MockServerHandle { addr, task })
+    }
+
+    // minimal http/1.1 handling; keeps the connection alive until the client closes it
+    async fn serve(&self, stream: TcpStream) -> io::Result<()> {
+        let mut stream = BufReader::new(stream);
+
+        loop {
+            let mut request_line = String::new();
+            if stream.read_line(&mut request_line).await? == 0 {
+                return Ok(());
+            }
+
+            let mut parts = request_line.split_whitespace();
+            let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "bad request line",
+                ));
+            };
+
+            let mut content_length = 0;
+            loop {
+                let mut header = String::new();
+                stream.read_line(&mut header).await?;
+                let header = header.trim_end();
+
+                if header.is_empty() {
+                    break;
+                }
+                if let Some((name, value)) = header.split_once(':') {
+                    if name.eq_ignore_ascii_case("content-length") {
+                        content_length = value.trim().parse().unwrap_or(0);
+                    }
+                }
+            }
+
+            let mut body = vec![0; content_length];
+            stream.read_exact(&mut body).await?;
+
+            let (status, body) = self.handle(method, target, &body);
+
+            let head = format!(
+                "HTTP/1.1 {status} {}\r\ncontent-type: application/json\r\ncontent-length: {}\r\n\r\n",
+                reason(status),
+                body.len()
+            );
+            let stream = stream.get_mut();
+            stream.write_all(head.as_bytes()).await?;
+            stream.write_all(&body).await?;
+            stream.flush().await?;
+        }
+    }
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        201 => "Created",
+        400 => "Bad Request",
+        404 => "Not Found",
+        409 => "Conflict",
+        _ => "Internal Server Error",
+    }
+}
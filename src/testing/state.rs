@@ -0,0 +1,476 @@
+use super::search::{search, SearchError};
+use bytes::Bytes;
+use serde_json::{json, Map, Value};
+use std::{
+    collections::{BTreeMap, HashMap},
+    time::SystemTime,
+};
+
+type Reply = (u16, Value);
+
+#[derive(Debug, Default)]
+pub(super) struct State {
+    collections: BTreeMap<String, Collection>,
+    aliases: BTreeMap<String, String>,
+    keys: BTreeMap<usize, Value>,
+    next_key_id: usize,
+}
+
+#[derive(Debug)]
+struct Collection {
+    schema: Map<String, Value>,
+    created_at: u64,
+    // kept in insertion order, which is the order of unsorted search results
+    documents: Vec<Map<String, Value>>,
+    next_id: usize,
+}
+
+impl Collection {
+    fn response(&self) -> Value {
+        let mut res = self.schema.clone();
+        res.entry("default_sorting_field").or_insert(json!(""));
+        res.entry("enable_nested_fields").or_insert(json!(false));
+        res.entry("symbols_to_index").or_insert(json!([]));
+        res.entry("token_separators").or_insert(json!([]));
+        res.insert("created_at".to_owned(), json!(self.created_at));
+        res.insert("num_documents".to_owned(), json!(self.documents.len()));
+
+        Value::Object(res)
+    }
+
+    fn position(&self, id: &str) -> Option<usize> {
+        self.documents
+            .iter()
+            .position(|doc| doc.get("id").and_then(Value::as_str) == Some(id))
+    }
+
+    /// Writes `document` with `action` (create, upsert, update or emplace), returning the stored document.
+    fn write(&mut self, action: &str, mut document: Map<String, Value>) -> Result<Value, Reply> {
+        let id = match document.get("id") {
+            Some(Value::String(id)) => id.clone(),
+            Some(_) => return Err(error(400, "Document's `id` field should be a string.")),
+            None if action == "update" => {
+                return Err(error(400, "For update, the `id` key must be provided."))
+            }
+            None => {
+                // skip ids that were given explicitly
+                while self.position(&self.next_id.to_string()).is_some() {
+                    self.next_id += 1;
+                }
+                let id = self.next_id.to_string();
+                self.next_id += 1;
+                document.insert("id".to_owned(), json!(id));
+                id
+            }
+        };
+
+        match (action, self.position(&id)) {
+            ("create", Some(_)) => Err(error(
+                409,
+                format!("A document with id {id} already exists."),
+            )),
+            ("update", None) => Err(error(
+                404,
+                format!("Could not find a document with id: {id}"),
+            )),
+            ("upsert", Some(i)) => {
+                self.documents[i] = document.clone();
+                Ok(Value::Object(document))
+            }
+            ("update" | "emplace", Some(i)) => {
+                merge(&mut self.documents[i], document);
+                Ok(Value::Object(self.documents[i].clone()))
+            }
+            ("create" | "upsert" | "emplace", None) => {
+                self.documents.push(document.clone());
+                Ok(Value::Object(document))
+            }
+            (action, _) => Err(error(400, format!("Invalid action `{action}`."))),
+        }
+    }
+}
+
+/// Updates `document` field by field, nested objects included; `null` removes the field.
+fn merge(document: &mut Map<String, Value>, update: Map<String, Value>) {
+    for (key, value) in update {
+        match (document.get_mut(&key), value) {
+            (_, Value::Null) => {
+                document.remove(&key);
+            }
+            (Some(Value::Object(nested)), Value::Object(value)) => merge(nested, value),
+            (_, value) => {
+                document.insert(key, value);
+            }
+        }
+    }
+}
+
+impl State {
+    pub(super) fn handle(
+        &mut self,
+        method: &str,
+        path: &str,
+        query: HashMap<String, String>,
+        body: &[u8],
+    ) -> (u16, Bytes) {
+        let segments = path
+            .split('/')
+            .filter(|s| !s.is_empty())
+            .collect::<Vec<_>>();
+
+        let reply = match (method, segments.as_slice()) {
+            // import and export are the only endpoints that don't speak json
+            ("POST", ["collections", name, "documents", "import"]) => {
+                return self.import(name, &query, body)
+            }
+            ("GET", ["collections", name, "documents", "export"]) => return self.export(name),
+            _ => match parse_body(body) {
+                Ok(body) => self.route(method, &segments, &query, body),
+                Err(reply) => reply,
+            },
+        };
+
+        let (status, value) = reply;
+        (status, Bytes::from(value.to_string()))
+    }
+
+    fn route(
+        &mut self,
+        method: &str,
+        segments: &[&str],
+        query: &HashMap<String, String>,
+        body: Value,
+    ) -> Reply {
+        let res = match (method, segments) {
+            ("GET", ["health"]) => Ok(json!({ "ok": true })),
+
+            ("GET", ["collections"]) => Ok(Value::Array(
+                self.collections
+                    .values()
+                    .map(Collection::response)
+                    .collect(),
+            )),
+            ("POST", ["collections"]) => self.create_collection(body),
+            ("GET", ["collections", name]) => self.collection(name).map(Collection::response),
+            ("PATCH", ["collections", name]) => self.update_collection(name, body),
+            ("DELETE", ["collections", name]) => self.delete_collection(name),
+
+            ("POST", ["collections", name, "documents"]) => {
+                let action = query.get("action").map_or("create", String::as_str);
+                self.write_document(name, action, body)
+            }
+            ("GET", ["collections", name, "documents", "search"]) => {
+                self.search(name, query.clone())
+            }
+            ("GET", ["collections", name, "documents", id]) => self
+                .collection(name)
+                .and_then(|c| document_not_found(c.position(id), id).map(|i| &c.documents[i]))
+                .map(|doc| Value::Object(doc.clone())),
+            ("PATCH", ["collections", name, "documents", id]) => {
+                let mut body = body;
+                if let Value::Object(doc) = &mut body {
+                    doc.insert("id".to_owned(), json!(id));
+                }
+                self.write_document(name, "update", body)
+            }
+            ("DELETE", ["collections", name, "documents", id]) => self
+                .collection_mut(name)
+                .and_then(|c| document_not_found(c.position(id), id).map(|i| c.documents.remove(i)))
+                .map(Value::Object),
+
+            ("POST", ["multi_search"]) => self.multi_search(query, body),
+
+            ("GET", ["aliases"]) => Ok(json!({
+                "aliases": self
+                    .aliases
+                    .iter()
+                    .map(|(name, collection_name)| alias(name, collection_name))
+                    .collect::<Vec<_>>()
+            })),
+            ("PUT", ["aliases", name]) => match body.get("collection_name") {
+                Some(Value::String(collection_name)) => {
+                    self.aliases
+                        .insert(name.to_string(), collection_name.clone());
+                    Ok(alias(name, collection_name))
+                }
+                _ => Err(error(400, "Parameter `collection_name` is required.")),
+            },
+            ("GET", ["aliases", name]) => self
+                .aliases
+                .get(*name)
+                .map(|collection_name| alias(name, collection_name))
+                .ok_or_else(|| error(404, "Not Found")),
+            ("DELETE", ["aliases", name]) => self
+                .aliases
+                .remove(*name)
+                .map(|collection_name| alias(name, &collection_name))
+                .ok_or_else(|| error(404, "Not Found")),
+
+            ("GET", ["keys"]) => Ok(json!({
+                "keys": self.keys.values().map(key_summary).collect::<Vec<_>>()
+            })),
+            ("POST", ["keys"]) => self.create_key(body),
+            ("GET", ["keys", id]) => self.key_id(id).map(|(_, key)| key_summary(key)),
+            ("DELETE", ["keys", id]) => self.key_id(id).map(|(id, _)| id).map(|id| {
+                self.keys.remove(&id);
+                json!({ "id": id })
+            }),
+
+            _ => Err(error(404, "Not Found")),
+        };
+
+        match res {
+            Ok(value) => (200, value),
+            Err(reply) => reply,
+        }
+    }
+
+    // collections can be addressed through their aliases
+    fn resolve<'a>(&'a self, name: &'a str) -> &'a str {
+        self.aliases.get(name).map_or(name, String::as_str)
+    }
+
+    fn collection(&self, name: &str) -> Result<&Collection, Reply> {
+        self.collections
+            .get(self.resolve(name))
+            .ok_or_else(|| collection_not_found(name))
+    }
+
+    fn collection_mut(&mut self, name: &str) -> Result<&mut Collection, Reply> {
+        let resolved = self.resolve(name).to_owned();
+        self.collections
+            .get_mut(&resolved)
+            .ok_or_else(|| collection_not_found(name))
+    }
+
+    fn create_collection(&mut self, body: Value) -> Result<Value, Reply> {
+        let Value::Object(schema) = body else {
+            return Err(error(400, "Bad JSON."));
+        };
+        let Some(name) = schema.get("name").and_then(Value::as_str) else {
+            return Err(error(400, "Parameter `name` is required."));
+        };
+        if self.collections.contains_key(name) {
+            return Err(error(
+                409,
+                format!("A collection with name `{name}` already exists."),
+            ));
+        }
+
+        let created_at = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+
+        let collection = Collection {
+            schema: schema.clone(),
+            created_at,
+            documents: Vec::new(),
+            next_id: 0,
+        };
+        let res = collection.response();
+        self.collections.insert(name.to_owned(), collection);
+
+        Ok(res)
+    }
+
+    fn update_collection(&mut self, name: &str, body: Value) -> Result<Value, Reply> {
+        let collection = self.collection_mut(name)?;
+        let Some(Value::Array(updates)) = body.get("fields") else {
+            return Err(error(
+                400,
+                "The `fields` value should be an array of objects.",
+            ));
+        };
+
+        let fields = collection
+            .schema
+            .entry("fields")
+            .or_insert_with(|| json!([]))
+            .as_array_mut()
+            .ok_or_else(|| error(500, "Collection fields are not an array."))?;
+
+        for update in updates {
+            let name = update.get("name");
+
+            if update.get("drop") == Some(&json!(true)) {
+                fields.retain(|f| f.get("name") != name);
+            } else {
+                fields.push(update.clone());
+            }
+        }
+
+        Ok(json!({ "fields": updates }))
+    }
+
+    fn delete_collection(&mut self, name: &str) -> Result<Value, Reply> {
+        let resolved = self.resolve(name).to_owned();
+        self.collections
+            .remove(&resolved)
+            .map(|c| c.response())
+            .ok_or_else(|| collection_not_found(name))
+    }
+
+    fn write_document(&mut self, name: &str, action: &str, body: Value) -> Result<Value, Reply> {
+        let Value::Object(document) = body else {
+            return Err(error(400, "Bad JSON."));
+        };
+
+        self.collection_mut(name)?.write(action, document)
+    }
+
+    fn import(&mut self, name: &str, query: &HashMap<String, String>, body: &[u8]) -> (u16, Bytes) {
+        let action = query.get("action").map_or("create", String::as_str);
+
+        let collection = match self.collection_mut(name) {
+            Ok(collection) => collection,
+            Err((status, value)) => return (status, Bytes::from(value.to_string())),
+        };
+
+        let mut res = String::new();
+        for line in String::from_utf8_lossy(body).lines() {
+            let result = match serde_json::from_str(line) {
+                Ok(Value::Object(document)) => collection.write(action, document),
+                _ => Err(error(400, "Bad JSON.")),
+            };
+
+            let line_res = match result {
+                Ok(_) => json!({ "success": true }),
+                Err((code, message)) => json!({
+                    "success": false,
+                    "code": code,
+                    "error": message["message"],
+                    "document": line,
+                }),
+            };
+            res.push_str(&line_res.to_string());
+            res.push('\n');
+        }
+
+        (200, Bytes::from(res))
+    }
+
+    fn export(&self, name: &str) -> (u16, Bytes) {
+        match self.collection(name) {
+            Ok(collection) => {
+                let lines = collection
+                    .documents
+                    .iter()
+                    .map(|doc| Value::Object(doc.clone()).to_string())
+                    .collect::<Vec<_>>();
+                (200, Bytes::from(lines.join("\n")))
+            }
+            Err((status, value)) => (status, Bytes::from(value.to_string())),
+        }
+    }
+
+    fn search(&self, name: &str, params: HashMap<String, String>) -> Result<Value, Reply> {
+        let collection = self.collection(name)?;
+
+        search(name, &collection.documents, &params).map_err(|e| match e {
+            SearchError::BadRequest(message) => error(400, message),
+        })
+    }
+
+    fn multi_search(&self, query: &HashMap<String, String>, body: Value) -> Result<Value, Reply> {
+        let Some(Value::Array(searches)) = body.get("searches") else {
+            return Err(error(400, "Missing `searches` array."));
+        };
+
+        let results = searches
+            .iter()
+            .map(|search| {
+                // parameters in the url apply to every search
+                let mut params = query.clone();
+                if let Value::Object(search) = search {
+                    for (key, value) in search {
+                        let value = match value {
+                            Value::String(s) => s.clone(),
+                            value => value.to_string(),
+                        };
+                        params.insert(key.clone(), value);
+                    }
+                }
+
+                let collection = params.get("collection").cloned().unwrap_or_default();
+                match self.search(&collection, params) {
+                    Ok(res) => res,
+                    Err((code, message)) => json!({ "code": code, "error": message["message"] }),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        Ok(json!({ "results": results }))
+    }
+
+    fn create_key(&mut self, body: Value) -> Result<Value, Reply> {
+        let Value::Object(mut key) = body else {
+            return Err(error(400, "Bad JSON."));
+        };
+        for field in ["actions", "collections"] {
+            if !key.get(field).is_some_and(Value::is_array) {
+                return Err(error(400, format!("Wrong format for `{field}`.")));
+            }
+        }
+
+        self.next_key_id += 1;
+        let id = self.next_key_id;
+        key.insert("id".to_owned(), json!(id));
+        key.entry("description").or_insert(json!(""));
+        key.entry("value")
+            .or_insert_with(|| json!(format!("mock{id:028}")));
+
+        let key = Value::Object(key);
+        self.keys.insert(id, key.clone());
+
+        Ok(key)
+    }
+
+    fn key_id(&self, id: &str) -> Result<(usize, &Value), Reply> {
+        id.parse()
+            .ok()
+            .and_then(|id| self.keys.get(&id).map(|key| (id, key)))
+            .ok_or_else(|| error(404, "Not Found"))
+    }
+}
+
+fn parse_body(body: &[u8]) -> Result<Value, Reply> {
+    if body.is_empty() {
+        return Ok(Value::Null);
+    }
+
+    serde_json::from_slice(body).map_err(|_| error(400, "Bad JSON."))
+}
+
+fn error(status: u16, message: impl Into<String>) -> Reply {
+    (status, json!({ "message": message.into() }))
+}
+
+fn collection_not_found(name: &str) -> Reply {
+    error(
+        404,
+        format!("Could not find a collection with name `{name}`."),
+    )
+}
+
+fn document_not_found(position: Option<usize>, id: &str) -> Result<usize, Reply> {
+    position.ok_or_else(|| error(404, format!("Could not find a document with id: {id}")))
+}
+
+fn alias(name: &str, collection_name: &str) -> Value {
+    json!({ "name": name, "collection_name": collection_name })
+}
+
+// listed keys only show the first characters of their value
+fn key_summary(key: &Value) -> Value {
+    let mut key = key.clone();
+
+    if let Value::Object(key) = &mut key {
+        if let Some(Value::String(value)) = key.remove("value") {
+            let prefix = value.chars().take(4).collect::<String>();
+            key.insert("value_prefix".to_owned(), json!(prefix));
+        }
+    }
+
+    key
+}
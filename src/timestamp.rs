@@ -0,0 +1,419 @@
+//! Dates stored as `int64` epoch timestamps, since typesense has no date type.
+//!
+//! Store dates as [`Timestamp`]s, or use [`seconds`] or [`millis`] with
+//! `#[serde(with = "typesensei::timestamp::seconds")]` and `#[typesensei(timestamp)]` on a date
+//! field, or an `Option` of one; the derive stores it as a sortable `int64` and uses the same
+//! encoding in partials.
+
+use __private::EpochField;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::{
+    marker::PhantomData,
+    time::{Duration, SystemTime},
+};
+
+/// Epoch timestamp in seconds, or in milliseconds as `Timestamp<Millis>`.
+///
+/// Serialized as a plain integer and stored as a sortable `int64` field by the derive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(transparent, bound = "")]
+pub struct Timestamp<P = Seconds> {
+    value: i64,
+    #[serde(skip)]
+    precision: PhantomData<P>,
+}
+
+/// Precision of a [`Timestamp`].
+pub trait Precision {
+    /// Milliseconds in one unit.
+    const MILLIS: i64;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Seconds {}
+
+impl Precision for Seconds {
+    const MILLIS: i64 = 1000;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Millis {}
+
+impl Precision for Millis {
+    const MILLIS: i64 = 1;
+}
+
+impl<P: Precision> Timestamp<P> {
+    /// Timestamp of `value` seconds, or milliseconds, since the epoch.
+    pub fn from_epoch(value: i64) -> Self {
+        Self {
+            value,
+            precision: PhantomData,
+        }
+    }
+
+    pub fn now() -> Self {
+        SystemTime::now().into()
+    }
+
+    /// Seconds, or milliseconds, since the epoch.
+    pub fn epoch(&self) -> i64 {
+        self.value
+    }
+
+    /// `None` if the time is out of range for the platform.
+    pub fn to_system_time(&self) -> Option<SystemTime> {
+        self.value
+            .checked_mul(P::MILLIS)
+            .and_then(SystemTime::from_millis)
+    }
+}
+
+impl<P: Precision> From<SystemTime> for Timestamp<P> {
+    fn from(time: SystemTime) -> Self {
+        Self::from_epoch(time.as_millis().div_euclid(P::MILLIS))
+    }
+}
+
+/// Time to filter timestamps stored in precision `P` by, see [`Filter::before`](crate::Filter::before).
+///
+/// [`Timestamp`]s only compare with fields of their own precision, other dates are converted.
+pub trait FilterTime<P: Precision> {
+    fn filter_value(&self) -> i64;
+}
+
+impl<P: Precision> FilterTime<P> for Timestamp<P> {
+    fn filter_value(&self) -> i64 {
+        self.value
+    }
+}
+
+impl<T: Epoch, P: Precision> FilterTime<P> for T {
+    fn filter_value(&self) -> i64 {
+        self.as_millis().div_euclid(P::MILLIS)
+    }
+}
+
+/// Date or time that can be stored as an epoch timestamp.
+pub trait Epoch: Sized {
+    fn as_millis(&self) -> i64;
+
+    /// `None` if the date is out of range.
+    fn from_millis(millis: i64) -> Option<Self>;
+}
+
+impl Epoch for SystemTime {
+    // floored like after the epoch, i.e. -1.5ms is -2ms, and saturated out of range
+    fn as_millis(&self) -> i64 {
+        let millis = match self.duration_since(SystemTime::UNIX_EPOCH) {
+            Ok(since) => since.as_millis() as i128,
+            Err(before) => -(before.duration().as_nanos().div_ceil(1_000_000) as i128),
+        };
+
+        millis.clamp(i64::MIN.into(), i64::MAX.into()) as i64
+    }
+
+    fn from_millis(millis: i64) -> Option<Self> {
+        let duration = Duration::from_millis(millis.unsigned_abs());
+
+        if millis < 0 {
+            SystemTime::UNIX_EPOCH.checked_sub(duration)
+        } else {
+            SystemTime::UNIX_EPOCH.checked_add(duration)
+        }
+    }
+}
+
+/// Epoch seconds.
+pub mod seconds {
+    use super::*;
+
+    pub fn serialize<T: EpochField, S: Serializer>(
+        value: &T,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        value.serialize_epoch(1000, serializer)
+    }
+
+    pub fn deserialize<'de, T: EpochField, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<T, D::Error> {
+        T::deserialize_epoch(1000, deserializer)
+    }
+}
+
+/// Epoch milliseconds.
+pub mod millis {
+    use super::*;
+
+    pub fn serialize<T: EpochField, S: Serializer>(
+        value: &T,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        value.serialize_epoch(1, serializer)
+    }
+
+    pub fn deserialize<'de, T: EpochField, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<T, D::Error> {
+        T::deserialize_epoch(1, deserializer)
+    }
+}
+
+mod __private {
+    use super::Epoch;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    // a date, or an optional one
+    pub trait EpochField: Sized {
+        fn serialize_epoch<S: Serializer>(
+            &self,
+            unit: i64,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error>;
+
+        fn deserialize_epoch<'de, D: Deserializer<'de>>(
+            unit: i64,
+            deserializer: D,
+        ) -> Result<Self, D::Error>;
+    }
+
+    fn from_epoch<T: Epoch, E: serde::de::Error>(value: i64, unit: i64) -> Result<T, E> {
+        value
+            .checked_mul(unit)
+            .and_then(T::from_millis)
+            .ok_or_else(|| E::custom(format!("timestamp {value} is out of range")))
+    }
+
+    impl<T: Epoch> EpochField for T {
+        fn serialize_epoch<S: Serializer>(
+            &self,
+            unit: i64,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            serializer.serialize_i64(self.as_millis().div_euclid(unit))
+        }
+
+        fn deserialize_epoch<'de, D: Deserializer<'de>>(
+            unit: i64,
+            deserializer: D,
+        ) -> Result<Self, D::Error> {
+            from_epoch(i64::deserialize(deserializer)?, unit)
+        }
+    }
+
+    impl<T: Epoch> EpochField for Option<T> {
+        fn serialize_epoch<S: Serializer>(
+            &self,
+            unit: i64,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            match self {
+                Some(value) => value.serialize_epoch(unit, serializer),
+                None => serializer.serialize_none(),
+            }
+        }
+
+        fn deserialize_epoch<'de, D: Deserializer<'de>>(
+            unit: i64,
+            deserializer: D,
+        ) -> Result<Self, D::Error> {
+            Option::<i64>::deserialize(deserializer)?
+                .map(|value| from_epoch(value, unit))
+                .transpose()
+        }
+    }
+}
+
+#[cfg(feature = "chrono")]
+mod chrono_impl {
+    use super::Epoch;
+    use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+
+    impl Epoch for DateTime<Utc> {
+        fn as_millis(&self) -> i64 {
+            self.timestamp_millis()
+        }
+
+        fn from_millis(millis: i64) -> Option<Self> {
+            DateTime::from_timestamp_millis(millis)
+        }
+    }
+
+    // midnight in utc
+    impl Epoch for NaiveDate {
+        fn as_millis(&self) -> i64 {
+            self.and_time(NaiveTime::MIN).and_utc().timestamp_millis()
+        }
+
+        fn from_millis(millis: i64) -> Option<Self> {
+            DateTime::from_timestamp_millis(millis).map(|date| date.date_naive())
+        }
+    }
+}
+
+#[cfg(feature = "time")]
+mod time_impl {
+    use super::Epoch;
+    use time::OffsetDateTime;
+
+    impl Epoch for OffsetDateTime {
+        fn as_millis(&self) -> i64 {
+            self.unix_timestamp_nanos().div_euclid(1_000_000) as i64
+        }
+
+        fn from_millis(millis: i64) -> Option<Self> {
+            OffsetDateTime::from_unix_timestamp_nanos(millis as i128 * 1_000_000).ok()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Partial, Patch, Typesense};
+    use serde_json::json;
+
+    #[derive(Debug, Serialize, Deserialize, Typesense)]
+    struct Event {
+        #[typesensei(timestamp)]
+        #[serde(with = "typesensei::timestamp::seconds")]
+        starts_at: SystemTime,
+        #[typesensei(timestamp)]
+        #[serde(with = "typesensei::timestamp::millis", default)]
+        ended_at: Option<SystemTime>,
+    }
+
+    #[derive(Debug, Serialize, Deserialize, Typesense)]
+    struct Post {
+        title: String,
+        published_at: Timestamp,
+        #[typesensei(sort = false)]
+        edited_at: Option<Timestamp<Millis>>,
+    }
+
+    #[test]
+    fn test_timestamp() {
+        let time = SystemTime::UNIX_EPOCH + Duration::from_millis(1_700_000_000_250);
+        let post = Post {
+            title: "Hello".to_owned(),
+            published_at: time.into(),
+            edited_at: Some(time.into()),
+        };
+
+        let json = serde_json::to_value(&post).unwrap();
+        assert_eq!(
+            json,
+            json!({"title": "Hello", "published_at": 1_700_000_000, "edited_at": 1_700_000_000_250i64})
+        );
+        let post = serde_json::from_value::<Post>(json).unwrap();
+        assert_eq!(post.published_at.epoch(), 1_700_000_000);
+        assert_eq!(post.edited_at.unwrap().to_system_time(), Some(time));
+
+        let before_epoch =
+            Timestamp::<Seconds>::from(SystemTime::UNIX_EPOCH - Duration::from_millis(1));
+        assert_eq!(before_epoch.epoch(), -1);
+    }
+
+    #[test]
+    fn test_system_time_millis() {
+        let epoch = SystemTime::UNIX_EPOCH;
+        assert_eq!((epoch + Duration::from_micros(1_500)).as_millis(), 1);
+        assert_eq!((epoch - Duration::from_micros(1_500)).as_millis(), -2);
+        assert_eq!((epoch - Duration::from_millis(2)).as_millis(), -2);
+        assert_eq!(
+            Timestamp::<Millis>::from(epoch - Duration::from_micros(1)).epoch(),
+            -1
+        );
+
+        // out of range times saturate instead of wrapping
+        if let Some(far) = epoch.checked_add(Duration::from_secs(i64::MAX as u64)) {
+            assert_eq!(far.as_millis(), i64::MAX);
+        }
+        if let Some(far) = epoch.checked_sub(Duration::from_secs(i64::MAX as u64)) {
+            assert_eq!(far.as_millis(), i64::MIN);
+        }
+
+        let schema = serde_json::to_value(Post::schema("posts").fields).unwrap();
+        assert_eq!(
+            schema,
+            json!([
+                {"name": "title", "type": "string"},
+                {"name": "published_at", "type": "int64", "sort": true},
+                {"name": "edited_at", "type": "int64", "sort": false, "optional": true},
+            ])
+        );
+    }
+
+    #[test]
+    fn test_foreign_timestamp_isnt_sortable() {
+        // i.e. `prost_types::Timestamp`, only the crate's own timestamps are sortable by default
+        mod proto {
+            use crate::{partial::TryFromPartialError, schema::Field, Partial, TypesenseField};
+            use serde::{Deserialize, Serialize};
+
+            #[derive(Debug, Serialize, Deserialize)]
+            pub struct Timestamp {
+                pub seconds: i64,
+                pub nanos: i32,
+            }
+
+            impl TypesenseField for Timestamp {
+                const TYPE: &'static str = Field::OBJECT;
+            }
+
+            impl Partial for Timestamp {
+                type Partial = Self;
+
+                fn into_partial(self) -> Self::Partial {
+                    self
+                }
+
+                fn from_partial(partial: Self::Partial) -> Result<Self, TryFromPartialError> {
+                    Ok(partial)
+                }
+            }
+        }
+
+        #[derive(Debug, Serialize, Deserialize, Typesense)]
+        struct Message {
+            sent_at: proto::Timestamp,
+        }
+
+        let schema = serde_json::to_value(Message::schema("messages").fields).unwrap();
+        assert_eq!(schema, json!([{"name": "sent_at", "type": "object"}]));
+    }
+
+    #[test]
+    fn test_timestamp_adapters() {
+        let starts_at = SystemTime::UNIX_EPOCH + Duration::from_millis(1_700_000_000_250);
+        let event = Event {
+            starts_at,
+            ended_at: None,
+        };
+
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json, json!({"starts_at": 1_700_000_000, "ended_at": null}));
+        let event = serde_json::from_value::<Event>(json!({"starts_at": -1})).unwrap();
+        assert_eq!(
+            event.starts_at,
+            SystemTime::UNIX_EPOCH - Duration::from_secs(1)
+        );
+        assert_eq!(event.ended_at, None);
+
+        let schema = serde_json::to_value(Event::schema("events").fields).unwrap();
+        assert_eq!(
+            schema,
+            json!([
+                {"name": "starts_at", "type": "int64", "sort": true},
+                {"name": "ended_at", "type": "int64", "sort": true, "optional": true},
+            ])
+        );
+
+        let partial = Event::partial().with_ended_at(Some(starts_at));
+        let json = serde_json::to_value(&partial).unwrap();
+        assert_eq!(json, json!({"ended_at": 1_700_000_000_250i64}));
+        let partial = serde_json::from_value::<<Event as Partial>::Partial>(json).unwrap();
+        assert_eq!(partial.ended_at, Patch::Value(starts_at));
+    }
+}
@@ -0,0 +1,16 @@
+use serde::{Deserialize, Serialize};
+use std::time::SystemTime;
+use typesensei::Typesense;
+
+#[derive(Debug, Serialize, Deserialize, Typesense)]
+struct Event {
+    starts_at: SystemTime,
+}
+
+#[derive(Debug, Serialize, Deserialize, Typesense)]
+struct Meeting {
+    #[typesensei(timestamp)]
+    starts_at: SystemTime,
+}
+
+fn main() {}
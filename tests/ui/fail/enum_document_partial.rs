@@ -0,0 +1,14 @@
+use serde::{Deserialize, Serialize};
+use typesensei::Typesense;
+
+#[derive(Debug, Serialize, Deserialize, Typesense)]
+#[serde(tag = "kind")]
+enum Event {
+    Click { page: String },
+    Purchase { amount: f64 },
+}
+
+fn main() {
+    // the variant, and so the tag sent with the partial, must be chosen
+    Event::partial();
+}
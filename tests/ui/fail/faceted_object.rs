@@ -0,0 +1,16 @@
+use serde::{Deserialize, Serialize};
+use typesensei::Typesense;
+
+#[derive(Debug, Serialize, Deserialize, Typesense)]
+struct Address {
+    city: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Typesense)]
+struct User {
+    name: String,
+    #[typesensei(ty = "object", facet = true)]
+    address: Address,
+}
+
+fn main() {}
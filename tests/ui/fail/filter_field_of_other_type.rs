@@ -0,0 +1,18 @@
+use serde::{Deserialize, Serialize};
+use typesensei::{Filter, Typesense, TypesenseField};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, TypesenseField)]
+enum OrderStatus {
+    Pending,
+    Paid,
+}
+
+#[derive(Debug, Serialize, Deserialize, Typesense)]
+struct Order {
+    title: String,
+    status: OrderStatus,
+}
+
+fn main() {
+    Filter::variant(Order::fields().title, OrderStatus::Paid);
+}
@@ -0,0 +1,16 @@
+use serde::{Deserialize, Serialize};
+use std::{fmt::Display, str::FromStr};
+use typesensei::{Typesense, TypesenseField};
+
+#[derive(Debug, Serialize, Deserialize, Typesense)]
+struct Wrapper<T>
+where
+    T: TypesenseField + Display + FromStr,
+    T::Err: Display,
+{
+    #[typesensei(ty = "string")]
+    #[serde(with = "typesensei::document_id")]
+    value: T,
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+use typesensei::Typesense;
+
+#[derive(Debug, Serialize, Deserialize, Typesense)]
+struct Book {
+    title: String,
+    #[typesensei(default_sorting_field)]
+    rating: Option<f32>,
+}
+
+fn main() {}
@@ -0,0 +1,15 @@
+use serde::{Deserialize, Serialize, Serializer};
+use typesensei::Typesense;
+
+fn as_cents<S: Serializer>(price: &f64, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_u64((price * 100.0) as u64)
+}
+
+#[derive(Debug, Serialize, Deserialize, Typesense)]
+struct Book {
+    title: String,
+    #[serde(serialize_with = "as_cents")]
+    price: f64,
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+use typesensei::TypesenseField;
+
+#[derive(Debug, Serialize, Deserialize, TypesenseField)]
+#[serde(rename_all = "snake_case")]
+enum Status {
+    Active,
+    #[serde(skip)]
+    Draft,
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+use serde::{Deserialize, Serialize};
+use typesensei::Typesense;
+
+#[derive(Debug, Serialize, Deserialize, Typesense)]
+struct Book {
+    title: String,
+    #[typesensei(default_sorting_field)]
+    rating: f32,
+    #[typesensei(default_sorting_field)]
+    page_count: u32,
+}
+
+fn main() {}
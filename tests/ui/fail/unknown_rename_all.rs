@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+use typesensei::Typesense;
+
+#[derive(Debug, Serialize, Deserialize, Typesense)]
+#[serde(rename_all = "camelcase")]
+struct Book {
+    title: String,
+    page_count: u32,
+}
+
+fn main() {}
@@ -0,0 +1,26 @@
+use serde::{Deserialize, Serialize};
+use typesensei::{Typesense, TypesenseField};
+
+#[derive(Debug, Serialize, Deserialize, Typesense)]
+#[serde(tag = "kind")]
+enum Event {
+    Click {
+        page: String,
+    },
+    #[serde(skip)]
+    Internal {
+        page: String,
+    },
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Debug, Serialize, Deserialize, TypesenseField)]
+enum Status {
+    #[serde(alias = "open")]
+    Pending,
+    #[serde(skip_serializing)]
+    Draft,
+}
+
+fn main() {}
@@ -0,0 +1,43 @@
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use typesensei::{Partial, Typesense};
+
+#[derive(Debug, Serialize, Deserialize, Typesense)]
+struct Page<M> {
+    title: String,
+    #[serde(flatten)]
+    meta: M,
+}
+
+#[derive(Debug, Serialize, Deserialize, Typesense)]
+struct Seo {
+    #[typesensei(facet = true)]
+    keywords: Vec<String>,
+    description: String,
+}
+
+fn main() {
+    let schema = Page::<Seo>::schema("pages");
+    let names = schema.fields.iter().map(|f| &*f.name).collect::<Vec<_>>();
+    assert_eq!(names, ["title", "keywords", "description"]);
+    assert_eq!(schema.fields[1].facet, Some(true));
+
+    let page = Page {
+        title: "Home".to_owned(),
+        meta: Seo {
+            keywords: vec!["home".to_owned()],
+            description: "Welcome".to_owned(),
+        },
+    };
+    let partial = page.into_partial();
+    assert_eq!(
+        serde_json::to_value(&partial).unwrap(),
+        json!({"title": "Home", "keywords": ["home"], "description": "Welcome"})
+    );
+
+    let partial = Page::<Seo>::partial().with_title("About".to_owned());
+    assert_eq!(
+        serde_json::to_value(&partial).unwrap(),
+        json!({"title": "About"})
+    );
+}
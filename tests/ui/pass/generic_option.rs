@@ -0,0 +1,22 @@
+use serde::{Deserialize, Serialize};
+use typesensei::{Partial, Patch, Typesense};
+
+#[derive(Debug, Serialize, Deserialize, Typesense)]
+struct Setting<V> {
+    key: String,
+    value: Option<V>,
+}
+
+fn main() {
+    let schema = Setting::<i32>::schema("settings");
+    assert_eq!(schema.fields[1].field_type, "int32");
+    assert_eq!(schema.fields[1].optional, Some(true));
+
+    let setting = Setting::<i32> {
+        key: "retries".to_owned(),
+        value: None,
+    };
+    let partial = setting.into_partial();
+    assert_eq!(partial.value, Patch::Null);
+    assert!(Setting::<i32>::from_partial(partial).is_ok());
+}
@@ -0,0 +1,25 @@
+use serde::{Deserialize, Serialize};
+use typesensei::{Partial, Typesense};
+
+#[derive(Debug, Serialize, Deserialize, Typesense)]
+struct Metric<V> {
+    name: String,
+    value: V,
+}
+
+fn main() {
+    let schema = Metric::<f64>::schema("metrics");
+    assert_eq!(schema.fields[1].name, "value");
+    assert_eq!(schema.fields[1].field_type, "float");
+
+    let schema = Metric::<String>::schema("labels");
+    assert_eq!(schema.fields[1].field_type, "string");
+
+    let metric = Metric {
+        name: "latency".to_owned(),
+        value: 1.5,
+    };
+    let partial = metric.into_partial();
+    assert_eq!(partial.value, Some(1.5));
+    assert_eq!(Metric::<f64>::partial().with_value(2.0).value, Some(2.0));
+}
@@ -0,0 +1,23 @@
+use serde::{Deserialize, Serialize};
+use typesensei::{Partial, Typesense};
+
+#[derive(Debug, Serialize, Deserialize, Typesense)]
+struct Series<V> {
+    name: String,
+    points: Vec<V>,
+}
+
+fn main() {
+    let schema = Series::<i64>::schema("series");
+    assert_eq!(schema.fields[1].field_type, "int64[]");
+
+    let schema = Series::<String>::schema("tags");
+    assert_eq!(schema.fields[1].field_type, "string[]");
+
+    let series = Series {
+        name: "visits".to_owned(),
+        points: vec![1i64, 2, 3],
+    };
+    let partial = series.into_partial();
+    assert_eq!(partial.points, Some(vec![1, 2, 3]));
+}
@@ -4,9 +4,10 @@ use std::fmt::{self, Debug, Display};
 use self::RenameRule::*;
 
 /// The different possible ways to change case of fields in a struct, or variants in an enum.
-#[derive(Copy, Clone, PartialEq, FromMeta)]
+#[derive(Copy, Clone, Default, PartialEq, FromMeta)]
 pub enum RenameRule {
     /// Don't apply a default rename rule.
+    #[default]
     None,
     /// Rename direct children to "lowercase" style.
     LowerCase,
@@ -29,12 +30,6 @@ pub enum RenameRule {
     ScreamingKebabCase,
 }
 
-impl Default for RenameRule {
-    fn default() -> Self {
-        Self::None
-    }
-}
-
 static RENAME_RULES: &[(&str, RenameRule)] = &[
     ("lowercase", LowerCase),
     ("UPPERCASE", UpperCase),
@@ -47,7 +42,7 @@ static RENAME_RULES: &[(&str, RenameRule)] = &[
 ];
 
 impl RenameRule {
-    pub fn from_str(rename_all_str: &str) -> Result<Self, ParseError> {
+    pub fn from_str(rename_all_str: &str) -> Result<Self, ParseError<'_>> {
         for (name, rule) in RENAME_RULES {
             if rename_all_str == *name {
                 return Ok(*rule);
@@ -58,7 +53,6 @@ impl RenameRule {
         })
     }
 
-    #[allow(dead_code)]
     /// Apply a renaming rule to an enum variant, returning the version expected in the source.
     pub fn apply_to_variant(&self, variant: &str) -> String {
         match *self {
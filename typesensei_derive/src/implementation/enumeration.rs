@@ -0,0 +1,148 @@
+use super::{rename_rule, Rename, Unsupported};
+use darling::{
+    ast::Data,
+    util::{Ignored, SpannedValue},
+    Error, FromDeriveInput, FromVariant, Result,
+};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{DeriveInput, Generics, Ident};
+
+#[derive(FromDeriveInput)]
+#[darling(supports(enum_unit), attributes(serde, typesensei))]
+pub struct DerivedEnum {
+    ident: Ident,
+    generics: Generics,
+    data: Data<Variant, Ignored>,
+
+    #[darling(rename = "crate")]
+    _serde: Option<String>,
+    #[darling(rename = "rename")]
+    _rename: Option<Rename>,
+    rename_all: Option<SpannedValue<Rename>>,
+}
+
+#[derive(FromVariant)]
+#[darling(attributes(serde, typesensei))]
+struct Variant {
+    ident: Ident,
+    rename: Option<Rename>,
+    // only change how the variant is read
+    #[darling(multiple, rename = "alias")]
+    _alias: Vec<String>,
+    #[darling(default, rename = "other")]
+    _other: bool,
+    #[darling(default, rename = "skip_deserializing")]
+    _skip_deserializing: bool,
+    // renames fields, which unit variants don't have
+    #[darling(rename = "rename_all")]
+    _rename_all: Option<Rename>,
+    // skipped variants could never be stored, so they are rejected
+    #[darling(default)]
+    skip: bool,
+
+    // serde options that change how the variant is sent
+    skip_serializing: Option<Unsupported>,
+    untagged: Option<Unsupported>,
+    with: Option<Unsupported>,
+    serialize_with: Option<Unsupported>,
+    deserialize_with: Option<Unsupported>,
+    bound: Option<Unsupported>,
+    borrow: Option<Unsupported>,
+}
+
+/// `TypesenseField`, `Partial` and `EnumField` for a unit enum stored as a string.
+pub fn impl_enum_field(input: &DeriveInput) -> Result<TokenStream> {
+    let DerivedEnum {
+        ident,
+        generics,
+        data,
+        rename_all,
+        ..
+    } = DerivedEnum::from_derive_input(input)?;
+
+    let case = rename_rule(&rename_all)?;
+
+    let variants = data.take_enum().expect("only unit enums should be derived");
+
+    let mut errors = Error::accumulator();
+    let mut values = Vec::new();
+    let mut arms = Vec::new();
+    for Variant {
+        ident,
+        rename,
+        skip,
+        skip_serializing,
+        untagged,
+        with,
+        serialize_with,
+        deserialize_with,
+        bound,
+        borrow,
+        ..
+    } in variants
+    {
+        let unsupported = [
+            ("skip_serializing", skip_serializing),
+            ("untagged", untagged),
+            ("with", with),
+            ("serialize_with", serialize_with),
+            ("deserialize_with", deserialize_with),
+            ("bound", bound),
+            ("borrow", borrow),
+        ];
+        for (name, option) in unsupported {
+            if let Some(option) = option {
+                errors.push(option.error(name, "variants of enum fields"));
+            }
+        }
+
+        if skip {
+            errors.push(
+                Error::custom("skipped variants can't be stored as a string field")
+                    .with_span(&ident),
+            );
+            continue;
+        }
+
+        let name = rename
+            .and_then(|r| r.serialize)
+            .unwrap_or_else(|| case.apply_to_variant(&ident.to_string()));
+        arms.push(quote!(Self::#ident => #name,));
+        values.push(name);
+    }
+
+    errors.finish()?;
+
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::typesensei::TypesenseField for #ident #ty_generics #where_clause {
+            const TYPE: &'static str = ::typesensei::schema::Field::STRING;
+        }
+
+        impl #impl_generics ::typesensei::partial::Partial for #ident #ty_generics #where_clause {
+            type Partial = Self;
+
+            fn into_partial(self) -> Self::Partial {
+                self
+            }
+
+            fn from_partial(
+                partial: Self::Partial,
+            ) -> Result<Self, ::typesensei::partial::TryFromPartialError> {
+                Ok(partial)
+            }
+        }
+
+        impl #impl_generics ::typesensei::EnumField for #ident #ty_generics #where_clause {
+            const VALUES: &'static [&'static str] = &[#(#values),*];
+
+            fn as_str(&self) -> &'static str {
+                match *self {
+                    #(#arms)*
+                }
+            }
+        }
+    })
+}
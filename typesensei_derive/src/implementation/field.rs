@@ -1,5 +1,7 @@
-use darling::FromField;
-use quote::format_ident;
+use super::StringList;
+use darling::{export::NestedMeta, util::Override, FromField, FromMeta};
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote, ToTokens};
 use syn::Ident;
 
 pub fn is_object(f: &Field) -> bool {
@@ -21,7 +23,6 @@ pub fn fields_has_id(fields: &[Field]) -> bool {
     fields.iter().any(field_is_id)
 }
 
-#[allow(dead_code)]
 pub fn field_is_id(field: &Field) -> bool {
     field.raw_ident == "id" || field.rename.as_ref().map(|r| r == "id").unwrap_or_default()
 }
@@ -39,7 +40,19 @@ pub struct Field {
     pub facet: Option<bool>,
     pub index: Option<bool>,
     pub sort: Option<bool>,
+    // serialized name, resolved from `serde_rename` and the container's `rename_all`
+    #[darling(skip)]
     pub rename: Option<String>,
+    #[darling(rename = "rename")]
+    pub serde_rename: Option<Rename>,
+    #[darling(multiple, rename = "alias")]
+    pub _alias: Vec<String>,
+    // serde's `default`, used for fields missing from a partial
+    pub default: Option<Override<String>>,
+    // custom serialization hides the serialized type, i.e. `with` to store numeric ids as strings
+    pub with: Option<String>,
+    pub serialize_with: Option<String>,
+    pub deserialize_with: Option<String>,
 
     // custom type for typesense
     #[darling(rename = "ty")]
@@ -54,11 +67,39 @@ pub struct Field {
     pub flatten: bool,
     #[darling(default)]
     pub skip: bool,
+    #[darling(default)]
+    pub skip_serializing: bool,
+    #[darling(default, rename = "skip_deserializing")]
+    pub _skip_deserializing: bool,
+    // the field is left out of some documents
+    pub skip_serializing_if: Option<String>,
+    // this field is the document id, which serde must still serialize as `id`
+    #[darling(default)]
+    pub id: bool,
+    // a date serialized as an epoch timestamp, i.e. with `typesensei::timestamp::seconds`
+    #[darling(default)]
+    pub timestamp: bool,
 
     // field is an object and has its own schema
     #[darling(default)]
     pub schema: bool,
 
+    pub locale: Option<String>,
+    pub infix: Option<bool>,
+    pub stem: Option<bool>,
+    pub range_index: Option<bool>,
+    pub store: Option<bool>,
+    // `collection.field` this field references for joins
+    pub reference: Option<String>,
+    pub token_separators: Option<StringList>,
+    pub symbols_to_index: Option<StringList>,
+
+    // vector field options
+    pub num_dim: Option<usize>,
+    pub vec_dist: Option<VecDist>,
+    pub hnsw_params: Option<HnswParams>,
+    pub embed: Option<Embed>,
+
     #[darling(skip, default)]
     pub is_option: Option<syn::Type>,
     #[darling(skip, default)]
@@ -77,5 +118,251 @@ impl Field {
             .ident
             .take()
             .expect("named struct should have named fields");
+        this.rename = this.serde_rename.as_ref().and_then(|r| r.serialize.clone());
+    }
+
+    /// Whether serde sends the field at all.
+    pub fn is_serialized(&self) -> bool {
+        !self.skip && !self.skip_serializing
+    }
+
+    /// Whether the serialized type is hidden by a custom serializer.
+    pub fn has_custom_serializer(&self) -> bool {
+        self.with.is_some() || self.serialize_with.is_some()
+    }
+
+    /// Function serde serializes the field with, forwarded to the partial.
+    pub fn serializer(&self) -> Option<syn::ExprPath> {
+        let path = match (&self.serialize_with, &self.with) {
+            (Some(path), _) => path.clone(),
+            (None, Some(with)) => format!("{with}::serialize"),
+            _ => return None,
+        };
+
+        // serde reports invalid paths
+        syn::parse_str(&path).ok()
+    }
+
+    /// Function serde deserializes the field with, forwarded to the partial.
+    pub fn deserializer(&self) -> Option<syn::ExprPath> {
+        let path = match (&self.deserialize_with, &self.with) {
+            (Some(path), _) => path.clone(),
+            (None, Some(with)) => format!("{with}::deserialize"),
+            _ => return None,
+        };
+
+        syn::parse_str(&path).ok()
+    }
+
+    /// Value of the field when it's missing, as serde would deserialize it.
+    pub fn default_value(&self) -> TokenStream {
+        match &self.default {
+            Some(Override::Explicit(path)) => {
+                let path = syn::parse_str::<syn::ExprPath>(path).expect("serde checks the path");
+                quote!(#path())
+            }
+            _ => quote!(::std::default::Default::default()),
+        }
+    }
+}
+
+/// serde's `rename = "..."` or `rename(serialize = "...", deserialize = "...")`.
+///
+/// Only the serialized name ends up in typesense.
+#[derive(Debug, Clone, Default)]
+pub struct Rename {
+    pub serialize: Option<String>,
+}
+
+impl FromMeta for Rename {
+    fn from_string(value: &str) -> darling::Result<Self> {
+        Ok(Self {
+            serialize: Some(value.to_owned()),
+        })
+    }
+
+    fn from_list(items: &[NestedMeta]) -> darling::Result<Self> {
+        #[derive(FromMeta)]
+        struct Names {
+            serialize: Option<String>,
+            #[darling(rename = "deserialize")]
+            _deserialize: Option<String>,
+        }
+
+        Names::from_list(items).map(|names| Self {
+            serialize: names.serialize,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, FromMeta)]
+pub enum VecDist {
+    #[darling(rename = "cosine")]
+    Cosine,
+    #[darling(rename = "ip")]
+    Ip,
+}
+
+impl ToTokens for VecDist {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        tokens.extend(match self {
+            Self::Cosine => quote!(::typesensei::schema::VectorDistance::Cosine),
+            Self::Ip => quote!(::typesensei::schema::VectorDistance::Ip),
+        });
+    }
+}
+
+#[derive(Debug, Clone, Copy, FromMeta)]
+pub struct HnswParams {
+    pub ef_construction: Option<usize>,
+    #[darling(rename = "M")]
+    pub m: Option<usize>,
+}
+
+impl ToTokens for HnswParams {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let ef_construction = option_tokens(&self.ef_construction);
+        let m = option_tokens(&self.m);
+
+        tokens.extend(quote! {
+            ::typesensei::schema::HnswParams {
+                ef_construction: #ef_construction,
+                m: #m,
+            }
+        });
+    }
+}
+
+#[derive(Debug, Clone, FromMeta)]
+pub struct Embed {
+    pub from: StringList,
+    pub model_config: ModelConfig,
+}
+
+impl ToTokens for Embed {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let from = &self.from.0;
+        let model_config = &self.model_config;
+
+        tokens.extend(quote! {
+            ::typesensei::schema::FieldEmbed {
+                from: vec![#(::std::borrow::Cow::Borrowed(#from)),*],
+                model_config: #model_config,
+            }
+        });
+    }
+}
+
+#[derive(Debug, Clone, FromMeta)]
+pub struct ModelConfig {
+    pub model_name: String,
+    pub api_key: Option<String>,
+    pub url: Option<String>,
+    pub access_token: Option<String>,
+    pub refresh_token: Option<String>,
+    pub client_id: Option<String>,
+    pub client_secret: Option<String>,
+    pub project_id: Option<String>,
+    pub indexing_prefix: Option<String>,
+    pub query_prefix: Option<String>,
+}
+
+impl ToTokens for ModelConfig {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let cow = |v: &Option<String>| match v {
+            Some(v) => quote!(Some(::std::borrow::Cow::Borrowed(#v))),
+            None => quote!(None),
+        };
+
+        let model_name = &self.model_name;
+        let api_key = cow(&self.api_key);
+        let url = cow(&self.url);
+        let access_token = cow(&self.access_token);
+        let refresh_token = cow(&self.refresh_token);
+        let client_id = cow(&self.client_id);
+        let client_secret = cow(&self.client_secret);
+        let project_id = cow(&self.project_id);
+        let indexing_prefix = cow(&self.indexing_prefix);
+        let query_prefix = cow(&self.query_prefix);
+
+        tokens.extend(quote! {
+            ::typesensei::schema::ModelConfig {
+                model_name: ::std::borrow::Cow::Borrowed(#model_name),
+                api_key: #api_key,
+                url: #url,
+                access_token: #access_token,
+                refresh_token: #refresh_token,
+                client_id: #client_id,
+                client_secret: #client_secret,
+                project_id: #project_id,
+                indexing_prefix: #indexing_prefix,
+                query_prefix: #query_prefix,
+            }
+        });
+    }
+}
+
+/// Optional schema attributes shared by struct fields and `extra_fields`.
+#[derive(Debug, Clone)]
+pub struct FieldAttrs<'a> {
+    pub locale: &'a Option<String>,
+    pub infix: &'a Option<bool>,
+    pub stem: &'a Option<bool>,
+    pub range_index: &'a Option<bool>,
+    pub store: &'a Option<bool>,
+    pub reference: &'a Option<String>,
+    pub token_separators: &'a Option<StringList>,
+    pub symbols_to_index: &'a Option<StringList>,
+    pub num_dim: &'a Option<usize>,
+    pub vec_dist: &'a Option<VecDist>,
+    pub hnsw_params: &'a Option<HnswParams>,
+    pub embed: &'a Option<Embed>,
+}
+
+impl ToTokens for FieldAttrs<'_> {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let cow = |v: &Option<String>| match v {
+            Some(v) => quote!(Some(::std::borrow::Cow::Borrowed(#v))),
+            None => quote!(None),
+        };
+        let cows = |v: &Option<StringList>| match v {
+            Some(StringList(v)) => quote!(Some(vec![#(::std::borrow::Cow::Borrowed(#v)),*])),
+            None => quote!(None),
+        };
+
+        let locale = cow(self.locale);
+        let infix = option_tokens(self.infix);
+        let stem = option_tokens(self.stem);
+        let range_index = option_tokens(self.range_index);
+        let store = option_tokens(self.store);
+        let reference = cow(self.reference);
+        let token_separators = cows(self.token_separators);
+        let symbols_to_index = cows(self.symbols_to_index);
+        let num_dim = option_tokens(self.num_dim);
+        let vec_dist = option_tokens(self.vec_dist);
+        let hnsw_params = option_tokens(self.hnsw_params);
+        let embed = option_tokens(self.embed);
+
+        tokens.extend(quote! {
+            locale: #locale,
+            infix: #infix,
+            stem: #stem,
+            range_index: #range_index,
+            store: #store,
+            reference: #reference,
+            token_separators: #token_separators,
+            symbols_to_index: #symbols_to_index,
+            num_dim: #num_dim,
+            vec_dist: #vec_dist,
+            hnsw_params: #hnsw_params,
+            embed: #embed,
+        });
+    }
+}
+
+pub fn option_tokens<T: ToTokens>(value: &Option<T>) -> TokenStream {
+    match value {
+        Some(v) => quote!(Some(#v)),
+        None => quote!(None),
     }
 }
@@ -0,0 +1,190 @@
+use super::impl_partial::{apply_fields, from_partial_fields, is_option, ImplPartial};
+use crate::implementation::Variant;
+use quote::{format_ident, quote, ToTokens};
+use syn::{Generics, Ident, Path, Token};
+
+/// Partial of an enum document: an enum of partial structs, one per variant,
+/// tagged the same way as the document.
+pub struct ImplEnumPartial<'a> {
+    pub vis: &'a syn::Visibility,
+    pub ident: &'a Ident,
+    pub variants: &'a Vec<Variant>,
+    pub serde: &'a Path,
+    pub tag: &'a Option<String>,
+    pub untagged: bool,
+}
+
+impl ToTokens for ImplEnumPartial<'_> {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        let Self {
+            vis,
+            ident,
+            variants,
+            serde,
+            tag,
+            untagged,
+        } = self;
+
+        let hash: Token![#] = Default::default();
+        let partial_ident = format_ident!("{}Partial", ident);
+        let generics = Generics::default();
+
+        let mut partial_variants = Vec::new();
+        let mut into_arms = Vec::new();
+        let mut from_arms = Vec::new();
+        let mut merge_arms = Vec::new();
+        let mut apply_arms = Vec::new();
+
+        for variant in variants.iter() {
+            let variant_ident = &variant.ident;
+            let struct_ident = format_ident!("{}{}", ident, variant_ident);
+            let fields = variant.fields();
+
+            let mut impl_partial = ImplPartial::new(
+                vis,
+                &struct_ident,
+                &generics,
+                fields,
+                &variant.skipped,
+                serde,
+            );
+            impl_partial.deny_unknown_fields = *untagged;
+            impl_partial.impl_struct(tokens);
+
+            let variant_partial = &impl_partial.partial_ident;
+            let rename = (!untagged).then(|| {
+                let tag_value = &variant.tag_value;
+                let aliases = &variant.alias;
+                quote!(#hash [serde(rename = #tag_value #(, alias = #aliases)*)])
+            });
+            partial_variants.push((
+                fields.len(),
+                quote! {
+                    #rename
+                    #variant_ident(#variant_partial),
+                },
+            ));
+
+            let idents = fields.iter().map(|f| &f.raw_ident).collect::<Vec<_>>();
+            let into_fields = fields.iter().map(|field| {
+                let i = &field.raw_ident;
+
+                if is_option(&field.ty) {
+                    quote!(#i: ::typesensei::partial::Partial::into_partial(#i),)
+                } else {
+                    quote!(#i: Some(::typesensei::partial::Partial::into_partial(#i)),)
+                }
+            });
+            into_arms.push(quote! {
+                Self::#variant_ident { #(#idents,)* .. } => #partial_ident::#variant_ident(#variant_partial {
+                    #(#into_fields)*
+                }),
+            });
+
+            let from_fields = from_partial_fields(fields, &variant.skipped);
+            merge_arms.push(quote! {
+                (#partial_ident::#variant_ident(partial), #partial_ident::#variant_ident(other)) => partial.merge(other),
+            });
+
+            // fields of the same variant are updated in place
+            let apply_fields = apply_fields(fields, |i| quote!(#i));
+            let partial = (!fields.is_empty())
+                .then(|| quote!(partial))
+                .unwrap_or(quote!(_));
+            apply_arms.push(quote! {
+                (Self::#variant_ident { #(#idents,)* .. }, #partial_ident::#variant_ident(#partial)) => {
+                    #apply_fields
+                }
+            });
+
+            from_arms.push(quote! {
+                #partial_ident::#variant_ident(partial) => Self::#variant_ident {
+                    #from_fields
+                },
+            });
+        }
+
+        // untagged partials deserialize as the first variant accepting all their fields,
+        // so a document is matched by the variant with the fewest fields that has all of its own
+        if *untagged {
+            partial_variants.sort_by_key(|(len, _)| *len);
+        }
+        let partial_variants = partial_variants.into_iter().map(|(_, tokens)| tokens);
+
+        let tagging = match tag {
+            Some(tag) if !untagged => quote!(#hash [serde(tag = #tag)]),
+            _ => quote!(#hash [serde(untagged)]),
+        };
+
+        tokens.extend(quote! {
+            #hash [derive(Debug, #serde ::Serialize, #serde ::Deserialize)]
+            #tagging
+            #vis enum #partial_ident {
+                #(#partial_variants)*
+            }
+
+            impl ::typesensei::partial::Partial for #ident {
+                type Partial = #partial_ident;
+
+                fn into_partial(self) -> Self::Partial {
+                    match self {
+                        #(#into_arms)*
+                    }
+                }
+
+                fn from_partial(partial: #partial_ident) -> Result<Self, ::typesensei::partial::TryFromPartialError> {
+                    Ok(match partial {
+                        #(#from_arms)*
+                    })
+                }
+
+                // another variant replaces the partial, or the document
+                #[allow(unreachable_patterns)]
+                fn merge_partial(partial: &mut #partial_ident, other: #partial_ident) {
+                    match (partial, other) {
+                        #(#merge_arms)*
+                        (partial, other) => *partial = other,
+                    }
+                }
+
+                #[allow(unreachable_patterns)]
+                fn apply_partial(&mut self, partial: #partial_ident) -> Result<(), ::typesensei::partial::TryFromPartialError> {
+                    match (self, partial) {
+                        #(#apply_arms)*
+                        (this, partial) => *this = ::typesensei::partial::Partial::from_partial(partial)?,
+                    }
+
+                    Ok(())
+                }
+            }
+
+            impl #partial_ident {
+                /// Merges `other` into this partial, as if it was sent after it.
+                pub fn merge(&mut self, other: Self) {
+                    <#ident as ::typesensei::partial::Partial>::merge_partial(self, other)
+                }
+
+                /// Updates `target` with this partial, as typesense would.
+                ///
+                /// `target` may be partly updated when this fails, see `Partial::apply_partial`.
+                pub fn apply_to(self, target: &mut #ident) -> Result<(), ::typesensei::partial::TryFromPartialError> {
+                    ::typesensei::partial::Partial::apply_partial(target, self)
+                }
+            }
+
+            impl From<#ident> for #partial_ident {
+                fn from(val: #ident) -> Self {
+                    ::typesensei::partial::Partial::into_partial(val)
+                }
+            }
+
+            impl std::convert::TryFrom<#partial_ident> for #ident {
+                type Error = ::typesensei::partial::TryFromPartialError;
+
+                fn try_from(val: #partial_ident) -> Result<Self, Self::Error> {
+                    <Self as ::typesensei::partial::Partial>::from_partial(val)
+                }
+            }
+        });
+    }
+}
@@ -1,11 +1,11 @@
 use quote::{format_ident, quote, ToTokens};
 use syn::{
-    punctuated::Punctuated,
+    ext::IdentExt,
     token::{Brace, Paren},
-    GenericParam, Generics, Ident, Path, Token, Type, WhereClause, WherePredicate,
+    Generics, Ident, Path, Token, Type, WherePredicate,
 };
 
-use crate::implementation::{case::RenameRule, Field};
+use crate::implementation::Field;
 
 pub struct ImplPartial<'a> {
     pub vis: &'a syn::Visibility,
@@ -13,9 +13,10 @@ pub struct ImplPartial<'a> {
     pub partial_ident: Ident,
     pub generics: Generics,
     pub fields: &'a Vec<Field>,
-    pub case: &'a RenameRule,
+    pub skipped: &'a [Field],
     pub serde: &'a Path,
-    pub rename_all: &'a Option<String>,
+    /// set for variants of untagged enums, so they only match their own fields
+    pub deny_unknown_fields: bool,
 }
 
 impl ToTokens for ImplPartial<'_> {
@@ -33,30 +34,108 @@ impl<'a> ImplPartial<'a> {
         ident: &'a Ident,
         generics: &'a Generics,
         fields: &'a Vec<Field>,
-        case: &'a RenameRule,
+        skipped: &'a [Field],
         serde: &'a Path,
-        rename_all: &'a Option<String>,
     ) -> Self {
         Self {
             vis,
             ident,
             partial_ident: format_ident!("{}Partial", ident),
-            generics: get_partial_generics(&generics, fields),
+            generics: get_partial_generics(generics, fields, serde),
             fields,
+            skipped,
             serde,
-            case,
-            rename_all,
+            deny_unknown_fields: false,
         }
     }
 
-    fn impl_struct(&self, tokens: &mut proc_macro2::TokenStream) {
+    // fields keep their serde adapters in partials, wrapped to handle `Option` and `Patch`
+    fn impl_custom_serde(&self, tokens: &mut proc_macro2::TokenStream) {
+        let Self {
+            partial_ident,
+            fields,
+            serde,
+            ..
+        } = self;
+
+        for field in fields.iter() {
+            let Some((serialize_fn, deserialize_fn)) = custom_serde_fns(partial_ident, field)
+            else {
+                continue;
+            };
+            let serialize_fn = format_ident!("{}", serialize_fn);
+            let deserialize_fn = format_ident!("{}", deserialize_fn);
+            let t = &field.ty;
+
+            // the field's own serializer takes the whole `Option`, so set values are cloned into one
+            let (ty, serialize, deserialize) = if is_option(t) {
+                (
+                    quote!(<#t as ::typesensei::Partial>::Partial),
+                    field.serializer().map(|f| quote! {
+                        match value {
+                            ::typesensei::Patch::Value(value) => #f(&Some(::std::clone::Clone::clone(value)), serializer),
+                            ::typesensei::Patch::Null => #serde ::Serializer::serialize_none(serializer),
+                            ::typesensei::Patch::Unchanged => Err(::typesensei::partial::unchanged_error()),
+                        }
+                    }),
+                    field.deserializer().map(|f| quote! {
+                        let value: #t = #f(deserializer)?;
+                        Ok(::typesensei::Patch::from(value))
+                    }),
+                )
+            } else {
+                (
+                    quote!(Option<<#t as ::typesensei::Partial>::Partial>),
+                    field.serializer().map(|f| {
+                        quote! {
+                            match value {
+                                Some(value) => #f(value, serializer),
+                                None => #serde ::Serializer::serialize_none(serializer),
+                            }
+                        }
+                    }),
+                    field.deserializer().map(|f| {
+                        quote! {
+                            let value: #t = #f(deserializer)?;
+                            Ok(Some(value))
+                        }
+                    }),
+                )
+            };
+            let serialize = serialize
+                .unwrap_or_else(|| quote!(#serde ::Serialize::serialize(value, serializer)));
+            let deserialize = deserialize
+                .unwrap_or_else(|| quote!(#serde ::Deserialize::deserialize(deserializer)));
+
+            tokens.extend(quote! {
+                #[doc(hidden)]
+                #[allow(non_snake_case)]
+                fn #serialize_fn<__S: #serde ::Serializer>(
+                    value: &#ty,
+                    serializer: __S,
+                ) -> ::std::result::Result<__S::Ok, __S::Error> {
+                    #serialize
+                }
+
+                #[doc(hidden)]
+                #[allow(non_snake_case)]
+                fn #deserialize_fn<'de, __D: #serde ::Deserializer<'de>>(
+                    deserializer: __D,
+                ) -> ::std::result::Result<#ty, __D::Error> {
+                    #deserialize
+                }
+            });
+        }
+    }
+
+    pub(super) fn impl_struct(&self, tokens: &mut proc_macro2::TokenStream) {
         let Self {
             vis,
             partial_ident,
             generics,
             fields,
-            rename_all,
             serde,
+            deny_unknown_fields,
             ..
         } = self;
 
@@ -64,9 +143,20 @@ impl<'a> ImplPartial<'a> {
 
         let hash: Token![#] = Default::default();
 
+        // serde doesn't support denying unknown fields next to flattened ones
+        let deny_unknown_fields = (*deny_unknown_fields && !fields.iter().any(|f| f.flatten))
+            .then(|| quote!(#hash [serde(deny_unknown_fields)]));
+
+        // derived bounds would require the type parameters themselves to be `Default` and serde,
+        // while only their partials are stored
+        let is_generic = generics.type_params().next().is_some();
+        let derive_default = (!is_generic).then(|| quote!(Default,));
+        let serde_bound = is_generic.then(|| quote!(#hash [serde(bound = "")]));
+
         tokens.extend(quote! {
-            #hash [derive(Debug, Default, #serde ::Serialize, #serde ::Deserialize)]
-            #rename_all
+            #hash [derive(Debug, #derive_default #serde ::Serialize, #serde ::Deserialize)]
+            #serde_bound
+            #deny_unknown_fields
             #vis struct #partial_ident #impl_generics #where_clause
         });
 
@@ -77,12 +167,25 @@ impl<'a> ImplPartial<'a> {
                     .as_ref()
                     .map(|r| quote!(#hash [serde(rename = #r)]));
 
-                braces.extend(quote!(#hash [serde(skip_serializing_if = "Option::is_none")]));
-
                 let i = &field.raw_ident;
                 let t = &field.ty;
 
-                // if type is Option, then it is already an Option
+                // optional fields are `Patch`es, which are sent as `null` when cleared
+                if is_option(t) {
+                    braces.extend(quote!(#hash [serde(default, skip_serializing_if = "::typesensei::Patch::is_unchanged")]));
+                } else {
+                    braces.extend(quote!(#hash [serde(skip_serializing_if = "Option::is_none")]));
+                }
+                if let Some((serialize_with, deserialize_with)) = custom_serde_fns(partial_ident, field) {
+                    braces.extend(quote!(#hash [serde(serialize_with = #serialize_with)]));
+                    // non-optional fields aren't defaulted to `None` by serde anymore
+                    let default = (!is_option(t)).then(|| quote!(, default));
+                    braces.extend(quote!(#hash [serde(deserialize_with = #deserialize_with #default)]));
+                }
+                if field.flatten {
+                    braces.extend(quote!(#hash [serde(flatten)]));
+                }
+
                 if is_option(t) {
                     braces.extend(quote! {
                         #rename
@@ -97,6 +200,22 @@ impl<'a> ImplPartial<'a> {
             }
         });
 
+        self.impl_custom_serde(tokens);
+
+        if is_generic {
+            let idents = fields.iter().map(|f| &f.raw_ident);
+
+            tokens.extend(quote! {
+                impl #impl_generics Default for #partial_ident #ty_generics #where_clause {
+                    fn default() -> Self {
+                        Self {
+                            #(#idents: Default::default(),)*
+                        }
+                    }
+                }
+            });
+        }
+
         tokens.extend(quote! {
             impl #impl_generics #partial_ident #ty_generics #where_clause
         });
@@ -109,52 +228,67 @@ impl<'a> ImplPartial<'a> {
                 let ty = quote! (<#t as ::typesensei::Partial>::Partial);
 
                 let with_fn = format_ident!("with_{}", i);
-                braces.extend(quote! { pub fn #with_fn (mut self, #i: #ty) -> Self });
-                Brace::default().surround(braces, |braces| {
-                    if is_option(t) {
-                        braces.extend(quote! {
-                            self. #i = #i;
+                let without_fn = format_ident!("without_{}", i);
+                let set_fn = format_ident!("set_{}", i);
+                let unset_fn = format_ident!("unset_{}", i);
+
+                // `None` clears optional fields, while unsetting leaves them alone
+                if is_option(t) {
+                    let clear_fn = format_ident!("clear_{}", i);
+
+                    braces.extend(quote! {
+                        pub fn #with_fn (mut self, #i: impl Into<#ty>) -> Self {
+                            self. #i = #i.into();
                             self
-                        });
-                    } else {
-                        braces.extend(quote! {
-                            self. #i = Some(#i);
+                        }
+
+                        pub fn #without_fn (mut self) -> Self {
+                            self. #i = ::typesensei::Patch::Unchanged;
                             self
-                        });
-                    }
-                });
+                        }
 
-                let without_fn = format_ident!("without_{}", i);
-                braces.extend(quote! { pub fn #without_fn (mut self) -> Self });
-                Brace::default().surround(braces, |braces| {
-                    braces.extend(quote! {
-                        self. #i = None;
-                        self
+                        pub fn #set_fn (&mut self, #i: impl Into<#ty>) {
+                            self. #i = #i.into();
+                        }
+
+                        pub fn #clear_fn (&mut self) {
+                            self. #i = ::typesensei::Patch::Null;
+                        }
+
+                        pub fn #unset_fn (&mut self) {
+                            self. #i = ::typesensei::Patch::Unchanged;
+                        }
                     });
-                });
+                } else {
+                    braces.extend(quote! {
+                        pub fn #with_fn (mut self, #i: #ty) -> Self {
+                            self. #i = Some(#i);
+                            self
+                        }
 
-                let set_fn = format_ident!("set_{}", i);
-                braces.extend(quote! { pub fn #set_fn (&mut self, #i: #ty) });
-                Brace::default().surround(braces, |braces| {
-                    if is_option(t) {
-                        braces.extend(quote! {
-                            self. #i = #i;
-                        });
-                    } else {
-                        braces.extend(quote! {
+                        pub fn #without_fn (mut self) -> Self {
+                            self. #i = None;
+                            self
+                        }
+
+                        pub fn #set_fn (&mut self, #i: #ty) {
                             self. #i = Some(#i);
-                        });
-                    }
-                });
+                        }
 
-                let unset_fn = format_ident!("unset_{}", i);
-                braces.extend(quote! { pub fn #unset_fn (&mut self) });
-                Brace::default().surround(braces, |braces| {
-                    braces.extend(quote! {
-                        self. #i = None;
+                        pub fn #unset_fn (&mut self) {
+                            self. #i = None;
+                        }
                     });
-                });
+                }
             }
+
+            let merge_fields = merge_fields(fields);
+            braces.extend(quote! {
+                /// Merges `other` into this partial, as if it was sent after it.
+                pub fn merge(&mut self, other: Self) {
+                    #merge_fields
+                }
+            });
         });
     }
 
@@ -164,6 +298,7 @@ impl<'a> ImplPartial<'a> {
             partial_ident,
             generics,
             fields,
+            skipped,
             ..
         } = self;
 
@@ -187,9 +322,9 @@ impl<'a> ImplPartial<'a> {
                         let i = &field.raw_ident;
 
                         if is_option(&field.ty) {
-                            braces.extend(quote!(#i: self. #i .map(|v| v.into_partial()),));
+                            braces.extend(quote!(#i: ::typesensei::partial::Partial::into_partial(self. #i),));
                         } else {
-                            braces.extend(quote!(#i: Some(self. #i .into_partial()),));
+                            braces.extend(quote!(#i: Some(::typesensei::partial::Partial::into_partial(self. #i)),));
                         }
                     }
                 });
@@ -205,21 +340,33 @@ impl<'a> ImplPartial<'a> {
                     parens.extend(quote!(Self));
 
                     Brace::default().surround(parens, |braces| {
-                        for field in fields.iter() {
-                            let i = &field.raw_ident;
-
-                            if is_option(&field.ty) {
-                                braces.extend(quote!(#i: <_>::from_partial(partial. #i)?,));
-                            } else {
-                                braces.extend(quote!(#i: <_>::from_partial(partial. #i .ok_or_else(|| ::typesensei::partial::TryFromPartialError {
-                                    type_name: core::any::type_name::<Self>(),
-                                    field: stringify!(#i),
-                                })?)?,));
-                            }
-                        }
+                        braces.extend(from_partial_fields(fields, skipped));
                     });
                 });
             });
+
+            let apply_fields = apply_fields(fields, |i| quote!(&mut self.#i));
+            braces.extend(quote! {
+                fn merge_partial(partial: &mut Self::Partial, other: Self::Partial) {
+                    partial.merge(other)
+                }
+
+                fn apply_partial(&mut self, partial: Self::Partial) -> Result<(), ::typesensei::partial::TryFromPartialError> {
+                    #apply_fields
+                    Ok(())
+                }
+            });
+        });
+
+        tokens.extend(quote! {
+            impl #impl_generics #partial_ident #ty_generics #where_clause {
+                /// Updates `target` with this partial, as typesense would.
+                ///
+                /// `target` may be partly updated when this fails, see `Partial::apply_partial`.
+                pub fn apply_to(self, target: &mut #ident #ty_generics) -> Result<(), ::typesensei::partial::TryFromPartialError> {
+                    ::typesensei::partial::Partial::apply_partial(target, self)
+                }
+            }
         });
     }
 
@@ -242,7 +389,7 @@ impl<'a> ImplPartial<'a> {
             braces.extend(quote!(fn from(val: #ident #ty_generics) -> Self));
 
             Brace::default().surround(braces, |braces| {
-                braces.extend(quote!(val.into_partial()));
+                braces.extend(quote!(::typesensei::partial::Partial::into_partial(val)));
             });
         });
     }
@@ -272,47 +419,145 @@ impl<'a> ImplPartial<'a> {
             );
 
             Brace::default().surround(braces, |braces| {
-                braces.extend(quote!(Self::from_partial(val)));
+                braces.extend(quote!(
+                    <Self as ::typesensei::partial::Partial>::from_partial(val)
+                ));
             });
         });
     }
 }
 
-fn get_partial_generics(generics: &Generics, fields: &Vec<Field>) -> Generics {
-    let mut generics = generics.clone();
+// names of the functions a custom serialized partial field is (de)serialized with
+fn custom_serde_fns(partial_ident: &Ident, field: &Field) -> Option<(String, String)> {
+    if field.flatten || (field.serializer().is_none() && field.deserializer().is_none()) {
+        return None;
+    }
+
+    let i = field.raw_ident.unraw();
+    Some((
+        format!("__{partial_ident}_serialize_{i}"),
+        format!("__{partial_ident}_deserialize_{i}"),
+    ))
+}
 
-    let mut bounds: Vec<WherePredicate> = Vec::new();
+/// Merges the fields of `other` into `self`; nested objects merge field by field.
+pub(super) fn merge_fields(fields: &[Field]) -> proc_macro2::TokenStream {
+    let mut tokens = proc_macro2::TokenStream::new();
 
     for field in fields {
-        let ty = &field.ty;
+        let i = &field.raw_ident;
+        let t = &field.ty;
+
+        tokens.extend(if is_option(t) {
+            quote!(<#t as ::typesensei::partial::Partial>::merge_partial(&mut self.#i, other.#i);)
+        } else {
+            quote! {
+                match (&mut self.#i, other.#i) {
+                    (Some(partial), Some(other)) => <#t as ::typesensei::partial::Partial>::merge_partial(partial, other),
+                    (partial, other @ Some(_)) => *partial = other,
+                    (_, None) => {}
+                }
+            }
+        });
+    }
 
-        let Some(ty) = contains_ty(&generics, ty) else {
-            continue;
-        };
+    tokens
+}
 
-        bounds.push(syn::parse_quote!(#ty : ::typesensei::Partial));
-    }
+/// Applies the fields of `partial` to the `target` of each field.
+pub(super) fn apply_fields(
+    fields: &[Field],
+    target: impl Fn(&Ident) -> proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let mut tokens = proc_macro2::TokenStream::new();
 
-    if bounds.is_empty() {
-        return generics;
+    for field in fields {
+        let i = &field.raw_ident;
+        let target = target(i);
+
+        tokens.extend(if is_option(&field.ty) {
+            quote!(::typesensei::partial::Partial::apply_partial(#target, partial.#i)?;)
+        } else {
+            quote! {
+                if let Some(partial) = partial.#i {
+                    ::typesensei::partial::Partial::apply_partial(#target, partial)?;
+                }
+            }
+        });
     }
 
-    if let Some(where_clause) = generics.where_clause.as_mut() {
-        where_clause.predicates.extend(bounds);
-    } else {
-        let mut predicates = Punctuated::new();
-        predicates.extend(bounds);
+    tokens
+}
+
+/// Fields of a document rebuilt from `partial`, with skipped and missing fields
+/// defaulted the way serde would.
+pub(super) fn from_partial_fields(fields: &[Field], skipped: &[Field]) -> proc_macro2::TokenStream {
+    let mut tokens = proc_macro2::TokenStream::new();
 
-        generics.where_clause.replace(WhereClause {
-            where_token: Default::default(),
-            predicates,
+    for field in fields {
+        let i = &field.raw_ident;
+
+        tokens.extend(if is_option(&field.ty) {
+            quote!(#i: ::typesensei::partial::Partial::from_partial(partial.#i)?,)
+        } else if field.default.is_some() {
+            let default = field.default_value();
+            quote! {
+                #i: match partial.#i {
+                    Some(v) => ::typesensei::partial::Partial::from_partial(v)?,
+                    None => #default,
+                },
+            }
+        } else {
+            quote! {
+                #i: ::typesensei::partial::Partial::from_partial(partial.#i.ok_or_else(|| ::typesensei::partial::TryFromPartialError {
+                    type_name: core::any::type_name::<Self>(),
+                    field: stringify!(#i),
+                })?)?,
+            }
         });
     }
 
+    for field in skipped {
+        let i = &field.raw_ident;
+        let default = field.default_value();
+        tokens.extend(quote!(#i: #default,));
+    }
+
+    tokens
+}
+
+fn get_partial_generics(generics: &Generics, fields: &[Field], serde: &Path) -> Generics {
+    let mut generics = generics.clone();
+
+    let bounds = partial_bounds(fields, serde);
+    if !bounds.is_empty() {
+        generics.make_where_clause().predicates.extend(bounds);
+    }
+
     generics
 }
 
-fn is_option(ty: &Type) -> bool {
+/// Bounds on the type parameters of fields for the document to be `Partial`.
+///
+/// Type parameters are bounded rather than field types, so that i.e. `<Option<T> as Partial>::Partial`
+/// still normalizes to `Patch<T::Partial>`.
+pub fn partial_bounds(fields: &[Field], serde: &Path) -> Vec<WherePredicate> {
+    fields
+        .iter()
+        .filter_map(|field| {
+            let ty = field.generic_type.as_ref()?;
+
+            Some(if field.is_vec.is_some() {
+                // `Vec<T>` is its own partial
+                syn::parse_quote!(#ty : ::std::fmt::Debug + #serde ::Serialize + #serde ::de::DeserializeOwned)
+            } else {
+                syn::parse_quote!(#ty : ::typesensei::Partial)
+            })
+        })
+        .collect()
+}
+
+pub(super) fn is_option(ty: &Type) -> bool {
     if let Type::Path(ty) = ty {
         if let Some(segment) = ty.path.segments.last() {
             if segment.ident == "Option" {
@@ -323,37 +568,3 @@ fn is_option(ty: &Type) -> bool {
 
     false
 }
-
-fn contains_ty(generics: &Generics, ty: &Type) -> Option<Ident> {
-    for param in &generics.params {
-        if let GenericParam::Type(t) = param {
-            if let Type::Path(ty) = ty {
-                if let Some(segment) = ty.path.segments.last() {
-                    if segment.ident == "Option" {
-                        if let syn::PathArguments::AngleBracketed(args) = &segment.arguments {
-                            if let Some(arg) = args.args.first() {
-                                if let syn::GenericArgument::Type(ty) = arg {
-                                    if let Type::Path(ty) = ty {
-                                        if let Some(ty) = ty.path.get_ident() {
-                                            if &t.ident == ty {
-                                                return Some(t.ident.clone());
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
-                } else {
-                    if let Some(ty) = ty.path.get_ident() {
-                        if &t.ident == ty {
-                            return Some(t.ident.clone());
-                        }
-                    }
-                }
-            }
-        }
-    }
-
-    None
-}
@@ -1,104 +1,261 @@
 use super::{super::case::RenameRule, Field};
-use crate::implementation::{ts, SymbolsToIndex, TypesenseFields};
+use crate::implementation::{field_is_id, ts, FieldAttrs, StringList, TypesenseFields, Variant};
 use darling::ToTokens;
-use quote::quote;
+use quote::{format_ident, quote, quote_spanned};
+use std::collections::HashMap;
 use syn::{
+    spanned::Spanned,
     token::{Brace, Bracket, Paren},
-    Generics, Ident, Type,
+    Generics, Ident,
 };
 
 pub struct ImplTypesense<'a> {
+    pub vis: &'a syn::Visibility,
     pub ident: &'a Ident,
     pub generics: &'a Generics,
-    pub id_type: &'a Type,
+    pub id_field: &'a Option<Field>,
+    pub tag: &'a Option<String>,
+    pub variants: &'a Option<Vec<Variant>>,
     pub enable_nested_fields: bool,
     pub fields: &'a Vec<Field>,
     pub case: &'a RenameRule,
     pub extra_fields: &'a Option<TypesenseFields>,
-    pub symbols_to_index: &'a Option<SymbolsToIndex>,
+    pub symbols_to_index: &'a Option<StringList>,
+    pub token_separators: &'a Option<StringList>,
+    pub metadata: &'a Option<HashMap<String, String>>,
+    pub voice_query_model: &'a Option<String>,
 }
 
 impl<'a> ToTokens for ImplTypesense<'a> {
     fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
         let Self {
+            vis,
             ident,
             generics,
-            id_type: _,
+            id_field,
+            tag,
+            variants,
             enable_nested_fields,
             fields,
             case,
             extra_fields,
             symbols_to_index,
+            token_separators,
+            metadata,
+            voice_query_model,
         } = self;
 
         let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
 
-        let fields_impl = FieldImpl::new(fields, case /*, id_type */);
+        let field_refs_impl = impl_field_refs(vis, ident, generics, fields);
+        let fields_impl = FieldImpl::new(fields, case);
+        let id_impl = impl_id(id_field, variants);
+
+        // the tag of an enum document tells its variant apart
+        let tag_impl = tag.as_ref().map(|tag| {
+            quote! {
+                .field(::typesensei::schema::Field {
+                    name: ::std::borrow::Cow::Borrowed(#tag),
+                    field_type: ::typesensei::schema::Field::STRING,
+                    facet: Some(true),
+                    ..Default::default()
+                })
+            }
+        });
         let extra_fields_impl = ExtraFieldImpl::new(extra_fields);
 
         let enable_nested_fields = enable_nested_fields.then(|| quote!(.enable_nested_fields()));
 
-        let symbols_to_index_impl = symbols_to_index.as_ref().map(|t| {
-            let symbols = &t.0;
-            let mut tokens = quote!(.symbols_to_index);
+        let symbols_to_index_impl = symbols_to_index
+            .as_ref()
+            .map(|t| impl_string_list(quote!(.symbols_to_index), t));
 
-            Paren::default().surround(&mut tokens, |parens| {
-                Bracket::default().surround(parens, |brackets| {
-                    for symbol in symbols {
-                        brackets.extend(quote!(#symbol ,));
-                    }
-                });
-            });
+        let token_separators_impl = token_separators
+            .as_ref()
+            .map(|t| impl_string_list(quote!(.token_separators), t));
+
+        let metadata_impl = metadata.as_ref().map(|metadata| {
+            let mut entries = metadata.iter().collect::<Vec<_>>();
+            entries.sort();
+
+            let mut tokens = proc_macro2::TokenStream::new();
+            for (key, value) in entries {
+                tokens.extend(quote!(.metadata_entry(#key, #value)));
+            }
 
             tokens
         });
 
+        let voice_query_model_impl = voice_query_model
+            .as_ref()
+            .map(|model_name| quote!(.voice_query_model(#model_name)));
+
         tokens.extend(quote! {
+            #field_refs_impl
+
             impl #impl_generics ::typesensei::Typesense for #ident #ty_generics
             #where_clause
             {
-                fn partial() -> Self::Partial {
-                    Default::default()
-                }
+                #id_impl
 
-                fn schema<'a>(collection_name: &'a str) -> ::typesensei::schema::CollectionSchema<'a> {
+                fn schema<'__schema>(collection_name: &'__schema str) -> ::typesensei::schema::CollectionSchema<'__schema> {
                     use ::typesensei::{Typesense, TypesenseField};
                     ::typesensei::schema::CollectionSchema::new(collection_name)
                     #enable_nested_fields
+                    #tag_impl
                     #fields_impl
                     #extra_fields_impl
                     #symbols_to_index_impl
+                    #token_separators_impl
+                    #metadata_impl
+                    #voice_query_model_impl
+                }
+            }
+        });
+    }
+}
+
+// typed names of the fields, so filters can only be built on fields of the right type
+fn impl_field_refs(
+    vis: &syn::Visibility,
+    ident: &Ident,
+    generics: &Generics,
+    fields: &[Field],
+) -> proc_macro2::TokenStream {
+    let fields_ident = format_ident!("{}Fields", ident);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    // flattened fields are stored under their own fields' names
+    let fields = fields.iter().filter(|f| !f.flatten).collect::<Vec<_>>();
+    let idents = fields.iter().map(|f| &f.raw_ident).collect::<Vec<_>>();
+    let types = fields.iter().map(|f| f.is_option.as_ref().unwrap_or(&f.ty));
+    let names = fields
+        .iter()
+        .map(|f| f.rename.clone().unwrap_or_else(|| f.raw_ident.to_string()));
+    let doc = format!("Typed names of the fields of [`{ident}`], see `{ident}::fields`.");
+
+    quote! {
+        #[doc = #doc]
+        #[derive(Debug, Clone, Copy)]
+        #vis struct #fields_ident #impl_generics #where_clause {
+            #(pub #idents: ::typesensei::FieldRef<#types>,)*
+            __document: ::std::marker::PhantomData<fn() -> #ident #ty_generics>,
+        }
+
+        impl #impl_generics #ident #ty_generics #where_clause {
+            /// Typed names of the fields, to build filters with.
+            #vis fn fields() -> #fields_ident #ty_generics {
+                #fields_ident {
+                    #(#idents: ::typesensei::FieldRef::new(#names),)*
+                    __document: ::std::marker::PhantomData,
                 }
             }
+        }
+    }
+}
+
+fn impl_string_list(
+    method: proc_macro2::TokenStream,
+    list: &StringList,
+) -> proc_macro2::TokenStream {
+    let mut tokens = method;
+
+    Paren::default().surround(&mut tokens, |parens| {
+        Bracket::default().surround(parens, |brackets| {
+            for item in &list.0 {
+                brackets.extend(quote!(#item ,));
+            }
         });
+    });
+
+    tokens
+}
+
+// documents without an id field get string ids generated by typesense
+fn impl_id(id_field: &Option<Field>, variants: &Option<Vec<Variant>>) -> proc_macro2::TokenStream {
+    let Some(Field {
+        raw_ident,
+        ty,
+        is_option,
+        ..
+    }) = id_field
+    else {
+        return quote! {
+            type Id = ::std::string::String;
+
+            fn id(&self) -> Option<Self::Id> {
+                None
+            }
+        };
+    };
+
+    let id_ty = is_option.as_ref().unwrap_or(ty);
+    let get_id = |id: proc_macro2::TokenStream| {
+        if is_option.is_some() {
+            quote!(::std::clone::Clone::clone(#id))
+        } else {
+            quote!(Some(::std::clone::Clone::clone(#id)))
+        }
+    };
+
+    let body = match variants {
+        None => get_id(quote!(&self.#raw_ident)),
+        Some(variants) => {
+            let arms = variants.iter().map(|variant| {
+                let ident = &variant.ident;
+                let id = variant
+                    .fields()
+                    .iter()
+                    .find(|f| field_is_id(f))
+                    .map(|f| &f.raw_ident)
+                    .expect("every variant has an id");
+                let get_id = get_id(quote!(#id));
+
+                quote!(Self::#ident { #id, .. } => #get_id,)
+            });
+
+            quote! {
+                match self {
+                    #(#arms)*
+                }
+            }
+        }
+    };
+
+    quote! {
+        type Id = #id_ty;
+
+        fn id(&self) -> Option<Self::Id> {
+            #body
+        }
     }
 }
 
 struct FieldImpl<'a> {
-    // id_type: &'a Type,
     fields: &'a Vec<Field>,
     case: &'a RenameRule,
 }
 
 impl<'a> FieldImpl<'a> {
-    fn new(fields: &'a Vec<Field>, case: &'a RenameRule /*, id_type: &'a Type */) -> Self {
-        Self {
-            fields,
-            case,
-            // id_type,
-        }
+    fn new(fields: &'a Vec<Field>, case: &'a RenameRule) -> Self {
+        Self { fields, case }
     }
 }
 
 impl<'a> ToTokens for FieldImpl<'a> {
     fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
         for field in self.fields {
+            // typesense manages the `id` field itself
+            if field_is_id(field) {
+                continue;
+            }
+
             if field.flatten {
-                impl_flatten_field(&field, tokens);
+                impl_flatten_field(field, tokens);
             } else if field.schema {
-                impl_schema_field(&field, &self.case, tokens);
+                impl_schema_field(field, self.case, tokens);
             } else {
-                impl_field(&field, &self.case, tokens);
+                impl_field(field, self.case, tokens);
             }
         }
     }
@@ -116,26 +273,69 @@ fn impl_field(field: &Field, case: &RenameRule, tokens: &mut proc_macro2::TokenS
         rename,
         custom_type,
         optional,
+        skip_serializing_if,
+        locale,
+        infix,
+        stem,
+        range_index,
+        store,
+        reference,
+        token_separators,
+        symbols_to_index,
+        num_dim,
+        vec_dist,
+        hnsw_params,
+        embed,
         ..
     } = field;
 
-    // if field_is_id(field) {
-    //     return;
-    // }
-
     let name = if let Some(name) = rename {
         name.to_owned()
     } else {
         case.apply_to_field(&raw_ident.to_string())
     };
 
-    let ty = custom_type
-        .as_ref()
-        .map(|t| quote!(#t))
-        .unwrap_or_else(|| quote!(< #ty >::TYPE));
+    // timestamps are mostly stored to be sorted by
+    let sort = match (sort, custom_type) {
+        (Some(sort), _) => quote!(Some(#sort)),
+        (None, _) if field.timestamp => quote!(Some(true)),
+        (None, Some(_)) => quote!(None),
+        (None, None) => quote_spanned!(ty.span()=> < #ty as ::typesensei::TypesenseField >::SORT),
+    };
+
+    let ty = match custom_type {
+        Some(t) => quote!(#t),
+        None if field.timestamp => {
+            let date = is_option.as_ref().unwrap_or(ty);
+            // only dates can be stored as epoch timestamps
+            quote_spanned! {date.span()=> {
+                let _ = <#date as ::typesensei::timestamp::Epoch>::as_millis;
+                ::typesensei::schema::Field::INT64
+            }}
+        }
+        None => quote_spanned!(ty.span()=> < #ty as ::typesensei::TypesenseField >::TYPE),
+    };
 
     let should_be_optional = index.map(|b| !b).unwrap_or(false);
-    let optional = optional.unwrap_or(false) || is_option.is_some() || should_be_optional;
+    let optional = optional.unwrap_or(false)
+        || is_option.is_some()
+        || skip_serializing_if.is_some()
+        || should_be_optional;
+
+    let attrs = FieldAttrs {
+        locale,
+        infix,
+        stem,
+        range_index,
+        store,
+        reference,
+        token_separators,
+        symbols_to_index,
+        num_dim,
+        vec_dist,
+        hnsw_params,
+        embed,
+    };
 
     impl_field_inner(
         tokens,
@@ -146,6 +346,7 @@ fn impl_field(field: &Field, case: &RenameRule, tokens: &mut proc_macro2::TokenS
         sort,
         optional,
         *default_sorting_field,
+        attrs,
     );
 }
 
@@ -164,7 +365,7 @@ fn impl_schema_field(field: &Field, case: &RenameRule, tokens: &mut proc_macro2:
         case.apply_to_field(&raw_ident.to_string())
     };
 
-    let ty = is_option.as_ref().map(|t| t).unwrap_or_else(|| ty);
+    let ty = is_option.as_ref().unwrap_or(ty);
 
     tokens.extend(quote!(.schema_field));
 
@@ -180,7 +381,6 @@ fn impl_flatten_field(field: &Field, tokens: &mut proc_macro2::TokenStream) {
         sort,
         facet,
         rename,
-        generic_type,
         ..
     } = field;
 
@@ -188,15 +388,10 @@ fn impl_flatten_field(field: &Field, tokens: &mut proc_macro2::TokenStream) {
 
     Paren::default().surround(tokens, |parens| {
         Brace::default().surround(parens, |braces| {
-            if let Some(ty) = generic_type.as_ref() {
-                braces.extend(quote! {
-                    let mut schema = <#ty>::schema("");
-                });
-            } else {
-                braces.extend(quote! {
-                    let mut schema = <#ty>::schema("");
-                });
-            }
+            // generic types are bounded by `Typesense` like any other flattened type
+            braces.extend(quote! {
+                let mut schema = <#ty as ::typesensei::Typesense>::schema("");
+            });
 
             let set_facet = facet.as_ref().map(|f| {
                 quote! {
@@ -266,6 +461,18 @@ impl<'a> ToTokens for ExtraFieldImpl<'a> {
                     sort,
                     optional,
                     default_sorting_field,
+                    locale,
+                    infix,
+                    stem,
+                    range_index,
+                    store,
+                    reference,
+                    token_separators,
+                    symbols_to_index,
+                    num_dim,
+                    vec_dist,
+                    hnsw_params,
+                    embed,
                 } = field;
 
                 let ty = quote!(#ty);
@@ -273,34 +480,51 @@ impl<'a> ToTokens for ExtraFieldImpl<'a> {
                 let optional = optional.unwrap_or(false);
                 let default_sorting_field = default_sorting_field.unwrap_or(false);
 
+                let attrs = FieldAttrs {
+                    locale,
+                    infix,
+                    stem,
+                    range_index,
+                    store,
+                    reference,
+                    token_separators,
+                    symbols_to_index,
+                    num_dim,
+                    vec_dist,
+                    hnsw_params,
+                    embed,
+                };
+
                 impl_field_inner(
                     tokens,
-                    &name,
+                    name,
                     ty,
-                    &index,
-                    &facet,
-                    &sort,
+                    index,
+                    facet,
+                    sort.map(|s| quote!(Some(#s))).unwrap_or(quote!(None)),
                     optional,
                     default_sorting_field,
+                    attrs,
                 );
             }
         }
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn impl_field_inner(
     tokens: &mut proc_macro2::TokenStream,
     name: &str,
     ty: proc_macro2::TokenStream,
     index: &Option<bool>,
     facet: &Option<bool>,
-    sort: &Option<bool>,
+    sort: proc_macro2::TokenStream,
     optional: bool,
     default_sorting_field: bool,
+    attrs: FieldAttrs,
 ) {
     let facet = facet.map(|f| quote!(Some(#f))).unwrap_or(quote!(None));
     let index = index.map(|i| quote!(Some(#i))).unwrap_or(quote!(None));
-    let sort = sort.map(|i| quote!(Some(#i))).unwrap_or(quote!(None));
     let optional = optional
         .then_some(quote!(Some(true)))
         .unwrap_or(quote!(None));
@@ -313,7 +537,8 @@ fn impl_field_inner(
             index: #index,
             sort: #sort,
             optional: #optional,
-            drop: None
+            drop: None,
+            #attrs
         })
     });
 
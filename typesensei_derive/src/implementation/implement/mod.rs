@@ -1,33 +1,47 @@
-use self::{impl_partial::ImplPartial, impl_typesense::ImplTypesense};
-use super::{case::RenameRule, Field, SymbolsToIndex, TypesenseFields};
+use self::{
+    impl_enum_partial::ImplEnumPartial, impl_partial::ImplPartial, impl_typesense::ImplTypesense,
+};
+use super::{case::RenameRule, Field, StringList, TypesenseFields, Variant};
 use darling::ToTokens;
 use proc_macro2::TokenStream;
-use syn::{Generics, Ident, Path, Type};
+use std::collections::HashMap;
+use syn::{Generics, Ident, Path};
 
+pub mod impl_enum_partial;
 pub mod impl_partial;
 pub mod impl_typesense;
 
 pub struct Implementor {
     pub vis: syn::Visibility,
     pub serde: Path,
-    pub rename_all: Option<String>,
     pub case: RenameRule,
-    pub id_type: Type,
+    pub id_field: Option<Field>,
+    pub tag: Option<String>,
+    pub untagged: bool,
+    // set for enum documents; `main_fields` are then the merged fields of all variants
+    pub variants: Option<Vec<Variant>>,
 
     pub ident: Ident,
     pub main_fields: Vec<Field>,
+    // fields serde never sends, so only rebuilt from their default
+    pub skipped_fields: Vec<Field>,
     pub main_generics: Generics,
+    // as declared; the partial adds its own bounds
+    pub generics: Generics,
 
     pub enable_nested_fields: bool,
     pub extra_fields: Option<TypesenseFields>,
-    pub symbols_to_index: Option<SymbolsToIndex>,
+    pub symbols_to_index: Option<StringList>,
+    pub token_separators: Option<StringList>,
+    pub metadata: Option<HashMap<String, String>>,
+    pub voice_query_model: Option<String>,
 }
 
 impl Implementor {
     pub fn impl_typesense(&self) -> TokenStream {
         pub struct Implementation<'a> {
             impl_typesense: ImplTypesense<'a>,
-            impl_partial: ImplPartial<'a>,
+            impl_partial: TokenStream,
         }
 
         impl<'a> ToTokens for Implementation<'a> {
@@ -44,38 +58,40 @@ impl Implementor {
 
         let Self {
             vis,
-            serde,
-            rename_all,
             case,
-            id_type,
+            id_field,
+            tag,
+            variants,
             ident,
             main_generics,
             main_fields,
             enable_nested_fields,
             extra_fields,
             symbols_to_index,
+            token_separators,
+            metadata,
+            voice_query_model,
+            ..
         } = self;
 
         let impl_typesense = ImplTypesense {
+            vis,
             ident,
             generics: main_generics,
             fields: main_fields,
-            id_type,
+            id_field,
+            tag,
+            variants,
             enable_nested_fields: *enable_nested_fields,
             case,
-            extra_fields: &extra_fields,
-            symbols_to_index: &symbols_to_index,
+            extra_fields,
+            symbols_to_index,
+            token_separators,
+            metadata,
+            voice_query_model,
         };
 
-        let impl_partial = ImplPartial::new(
-            vis,
-            ident,
-            main_generics,
-            main_fields,
-            case,
-            serde,
-            rename_all,
-        );
+        let impl_partial = self.impl_partial();
 
         let implementation = Implementation {
             impl_typesense,
@@ -89,23 +105,31 @@ impl Implementor {
         let Self {
             vis,
             serde,
-            rename_all,
-            case,
             ident,
-            main_generics,
+            generics,
             main_fields,
+            skipped_fields,
+            tag,
+            untagged,
+            variants,
             ..
         } = self;
 
-        let impl_partial = ImplPartial::new(
-            vis,
-            ident,
-            main_generics,
-            main_fields,
-            case,
-            serde,
-            rename_all,
-        );
+        if let Some(variants) = variants {
+            let impl_partial = ImplEnumPartial {
+                vis,
+                ident,
+                variants,
+                serde,
+                tag,
+                untagged: *untagged,
+            };
+
+            return impl_partial.to_token_stream();
+        }
+
+        let impl_partial =
+            ImplPartial::new(vis, ident, generics, main_fields, skipped_fields, serde);
 
         impl_partial.to_token_stream()
     }
@@ -2,11 +2,19 @@ use self::implement::Implementor;
 use darling::Result;
 use syn::DeriveInput;
 
+// darling's derived parsers trip `manual_unwrap_or_default` on `#[darling(default)]`
 pub mod case;
+#[allow(clippy::manual_unwrap_or_default)]
+pub mod enumeration;
+#[allow(clippy::manual_unwrap_or_default)]
 pub mod field;
 pub use field::*;
+#[allow(clippy::manual_unwrap_or_default)]
 pub mod parse;
 pub use parse::*;
+#[allow(clippy::manual_unwrap_or_default)]
+pub mod variant;
+pub use variant::*;
 pub mod implement;
 
 pub fn impl_typesense(input: DeriveInput) -> Result<proc_macro2::TokenStream> {
@@ -22,3 +30,7 @@ pub fn impl_partial(input: DeriveInput) -> Result<proc_macro2::TokenStream> {
 
     Ok(implementation)
 }
+
+pub fn impl_typesense_field(input: DeriveInput) -> Result<proc_macro2::TokenStream> {
+    enumeration::impl_enum_field(&input)
+}
@@ -1,36 +1,52 @@
-use super::{case::RenameRule, is_object, is_object_array, Field, Implementor};
-use darling::{export::NestedMeta, Error, FromDeriveInput, FromMeta, Result};
+use super::{
+    case::RenameRule, field_is_id, implement::impl_partial::partial_bounds, is_object,
+    is_object_array, Field, Implementor, Rename, Variant,
+};
+use darling::{
+    ast::Data, export::NestedMeta, util::SpannedValue, Error, FromDeriveInput, FromMeta, Result,
+};
 use proc_macro2::TokenStream;
-use proc_macro_error::abort;
 use quote::quote;
+use std::collections::HashMap;
 use syn::{
-    punctuated::Punctuated, DeriveInput, GenericArgument, GenericParam, Generics, Ident, Meta,
-    Path, PathArguments, Type, Visibility, WhereClause, WherePredicate,
+    DeriveInput, GenericArgument, GenericParam, Generics, Ident, Meta, Path, PathArguments, Type,
+    Visibility,
 };
 
 #[derive(FromDeriveInput)]
-#[darling(supports(struct_named), attributes(serde, typesensei))]
+#[darling(
+    supports(struct_named, enum_named, enum_unit),
+    attributes(serde, typesensei)
+)]
 pub struct Derived {
     vis: Visibility,
     ident: Ident,
     generics: Generics,
-    data: darling::ast::Data<(), Field>,
+    data: Data<Variant, Field>,
 
     #[darling(rename = "crate", default = "default_serde")]
     serde: String,
     #[darling(rename = "rename")]
-    _rename: Option<String>,
-    rename_all: Option<String>,
-    symbols_to_index: Option<SymbolsToIndex>,
+    _rename: Option<Rename>,
+    rename_all: Option<SpannedValue<Rename>>,
+    // enums are stored either internally tagged or untagged
+    tag: Option<String>,
+    #[darling(default)]
+    untagged: bool,
+    content: Option<String>,
+    symbols_to_index: Option<StringList>,
+    token_separators: Option<StringList>,
+    metadata: Option<HashMap<String, String>>,
+    voice_query_model: Option<String>,
     #[darling(default)]
     enable_nested_fields: bool,
     extra_fields: Option<TypesenseFields>,
 }
 
 #[derive(Debug, Clone)]
-pub struct SymbolsToIndex(pub Vec<String>);
+pub struct StringList(pub Vec<String>);
 
-impl FromMeta for SymbolsToIndex {
+impl FromMeta for StringList {
     fn from_meta(item: &syn::Meta) -> Result<Self> {
         (match *item {
             Meta::Path(_) => Self::from_word(),
@@ -45,9 +61,9 @@ impl FromMeta for SymbolsToIndex {
     fn from_list(items: &[NestedMeta]) -> Result<Self> {
         items
             .iter()
-            .map(|i| String::from_nested_meta(i))
+            .map(String::from_nested_meta)
             .collect::<Result<Vec<_>>>()
-            .map(|t| Self(t))
+            .map(Self)
     }
 }
 
@@ -69,13 +85,14 @@ impl FromMeta for TypesenseFields {
     fn from_list(items: &[NestedMeta]) -> Result<Self> {
         items
             .iter()
-            .map(|i| ts::Field::from_nested_meta(i))
+            .map(ts::Field::from_nested_meta)
             .collect::<Result<Vec<_>>>()
-            .map(|t| Self(t))
+            .map(Self)
     }
 }
 
 pub mod ts {
+    use crate::implementation::{Embed, HnswParams, StringList, VecDist};
     use darling::FromMeta;
 
     #[derive(Debug, Clone, FromMeta)]
@@ -87,6 +104,18 @@ pub mod ts {
         pub sort: Option<bool>,
         pub optional: Option<bool>,
         pub default_sorting_field: Option<bool>,
+        pub locale: Option<String>,
+        pub infix: Option<bool>,
+        pub stem: Option<bool>,
+        pub range_index: Option<bool>,
+        pub store: Option<bool>,
+        pub reference: Option<String>,
+        pub token_separators: Option<StringList>,
+        pub symbols_to_index: Option<StringList>,
+        pub num_dim: Option<usize>,
+        pub vec_dist: Option<VecDist>,
+        pub hnsw_params: Option<HnswParams>,
+        pub embed: Option<Embed>,
     }
 }
 
@@ -105,31 +134,67 @@ impl Implementor {
             serde,
             _rename,
             rename_all,
+            tag,
+            untagged,
+            content,
             symbols_to_index,
+            token_separators,
+            metadata,
+            voice_query_model,
             mut enable_nested_fields,
             extra_fields,
-        } = Derived::from_derive_input(&input)?;
+        } = Derived::from_derive_input(input)?;
 
         let serde = syn::parse_str(&serde)?;
 
-        let fields = data
-            .take_struct()
-            .expect("only named struct should be derived")
-            .fields;
-        let mut fields = fields.into_iter().filter(|f| !f.skip).collect::<Vec<_>>();
-        fields.iter_mut().for_each(Field::post_process);
-        mark_field_types(&generics, &mut fields);
+        let case = rename_rule(&rename_all)?;
+
+        let (fields, skipped_fields, variants) = match data {
+            Data::Struct(fields) => {
+                let (fields, skipped_fields) = prepare_fields(&generics, fields.fields, &case)?;
+                (fields, skipped_fields, None)
+            }
+            Data::Enum(variants) => {
+                if content.is_some() {
+                    return Err(Error::custom(
+                        "adjacently tagged enums are not supported, remove `content` to tag them internally",
+                    )
+                    .with_span(&ident));
+                }
+                if tag.is_none() && !untagged {
+                    return Err(Error::custom(
+                        "enum documents must be `#[serde(tag = \"...\")]` or `#[serde(untagged)]`",
+                    )
+                    .with_span(&ident));
+                }
+
+                let variants = prepare_variants(&ident, &generics, variants, &case)?;
+                (
+                    merge_variant_fields(&variants, &tag)?,
+                    Vec::new(),
+                    Some(variants),
+                )
+            }
+        };
 
         if !enable_nested_fields {
             enable_nested_fields = fields.iter().any(|f| is_object(f) || is_object_array(f));
         }
 
-        let case = if let Some(rename_all) = &rename_all {
-            RenameRule::from_str(&rename_all).map_err(|e| darling::Error::custom(e))?
-        } else {
+        // serde's `rename_all` renames the variants of an enum, not its fields
+        let case = if variants.is_some() {
             Default::default()
+        } else {
+            case
         };
 
+        // every variant must have the id for the enum to have one
+        let id_field = id_field(&fields)?.filter(|_| {
+            variants
+                .as_ref()
+                .is_none_or(|variants| variants.iter().all(|v| v.fields().iter().any(field_is_id)))
+        });
+
         let mut main_generics = generics.clone();
         let main_fields = fields.clone();
 
@@ -143,19 +208,140 @@ impl Implementor {
         Ok(Self {
             vis,
             serde,
-            rename_all,
             case,
-            id_type: id_type(&fields)?,
+            id_field,
+            tag,
+            untagged,
+            variants,
 
             ident,
             main_fields,
+            skipped_fields,
             main_generics,
+            generics,
 
             enable_nested_fields,
             extra_fields,
             symbols_to_index,
+            token_separators,
+            metadata,
+            voice_query_model,
+        })
+    }
+}
+
+// fields serde sends, and the ones it never does
+fn prepare_fields(
+    generics: &Generics,
+    mut fields: Vec<Field>,
+    case: &RenameRule,
+) -> Result<(Vec<Field>, Vec<Field>)> {
+    fields.iter_mut().for_each(Field::post_process);
+    let (mut fields, skipped) = fields
+        .into_iter()
+        .partition::<Vec<_>, _>(Field::is_serialized);
+
+    // flattened fields are serialized under their own fields' names
+    for field in fields
+        .iter_mut()
+        .filter(|f| !f.flatten && f.rename.is_none())
+    {
+        field.rename = Some(case.apply_to_field(&field.raw_ident.to_string()));
+    }
+
+    mark_field_types(generics, &mut fields);
+    validate_references(&fields)?;
+    validate_fields(&fields)?;
+
+    Ok((fields, skipped))
+}
+
+fn prepare_variants(
+    ident: &Ident,
+    generics: &Generics,
+    variants: Vec<Variant>,
+    case: &RenameRule,
+) -> Result<Vec<Variant>> {
+    // each variant gets its own partial struct, which can't share the enum's type parameters
+    if generics.type_params().next().is_some() {
+        return Err(Error::custom("generic enum documents are not supported").with_span(ident));
+    }
+    if variants.is_empty() {
+        return Err(
+            Error::custom("enum documents must have at least one variant").with_span(ident),
+        );
+    }
+
+    let mut errors = Error::accumulator();
+    for variant in &variants {
+        for error in variant.unsupported() {
+            errors.push(error);
+        }
+    }
+    errors.finish()?;
+
+    variants
+        .into_iter()
+        .map(|mut variant| {
+            let fields = std::mem::take(&mut variant.fields.fields);
+            // the enum's `rename_all` renames the variants, the variant's renames its fields
+            let fields_case = rename_rule(&variant.rename_all)?;
+            (variant.fields.fields, variant.skipped) =
+                prepare_fields(generics, fields, &fields_case)?;
+            variant.tag_value = variant
+                .rename
+                .as_ref()
+                .and_then(|r| r.serialize.clone())
+                .unwrap_or_else(|| case.apply_to_variant(&variant.ident.to_string()));
+
+            Ok(variant)
         })
+        .collect()
+}
+
+// fields of all variants by their serialized name; fields missing from some variants are optional
+fn merge_variant_fields(variants: &[Variant], tag: &Option<String>) -> Result<Vec<Field>> {
+    let name = |field: &Field| {
+        field
+            .rename
+            .clone()
+            .unwrap_or_else(|| field.raw_ident.to_string())
+    };
+    let ty = |field: &Field| {
+        let ty = &field.ty;
+        quote!(#ty).to_string()
+    };
+
+    let mut merged: Vec<(Field, usize)> = Vec::new();
+    for field in variants.iter().flat_map(|v| v.fields()) {
+        if tag.as_ref() == Some(&name(field)) {
+            return Err(
+                Error::custom("field has the same name as the tag").with_span(&field.raw_ident)
+            );
+        }
+
+        match merged.iter_mut().find(|(f, _)| name(f) == name(field)) {
+            Some((f, _)) if ty(f) != ty(field) => {
+                return Err(Error::custom(format!(
+                    "field `{}` must have the same type in every variant",
+                    name(field)
+                ))
+                .with_span(&field.ty));
+            }
+            Some((_, count)) => *count += 1,
+            None => merged.push((field.clone(), 1)),
+        }
     }
+
+    Ok(merged
+        .into_iter()
+        .map(|(mut field, count)| {
+            if count < variants.len() {
+                field.optional = Some(true);
+            }
+            field
+        })
+        .collect())
 }
 
 fn mark_field_types(generics: &Generics, fields: &mut Vec<Field>) {
@@ -180,11 +366,6 @@ fn mark_field_types(generics: &Generics, fields: &mut Vec<Field>) {
                 continue;
             };
 
-            // object array types must be Vec
-            if is_object_array(field) && seg.ident != "Vec" {
-                abort!(path, "'object[]' field must be of type Vec<_>")
-            }
-
             if seg.ident == "Option" || seg.ident == "Vec" {
                 let PathArguments::AngleBracketed(args) = &seg.arguments else {
                     unreachable!("Option and Vec must have angle bracketed arguments")
@@ -219,47 +400,164 @@ fn add_generic_bounds(
     impl_trait: TokenStream,
     serde: &Path,
 ) {
-    let mut bounds: Vec<WherePredicate> = Vec::new();
+    let mut bounds = partial_bounds(fields, serde);
 
     for field in fields {
-        if let Some(ty) = field.generic_type.as_ref() {
-            if field.flatten {
-                bounds.push(syn::parse_quote!(#ty : #impl_trait));
-            } else {
-                bounds.push(syn::parse_quote!(for<'de> #ty : ::std::fmt::Debug + Default + #serde ::Serialize + #serde ::Deserialize<'de> + ::typesensei::traits::TypesenseField));
-                bounds
-                    .push(syn::parse_quote!(<#ty as ::typesensei::traits::TypesenseField>::Type : 'static));
-            }
+        let Some(ty) = field.generic_type.as_ref() else {
+            continue;
+        };
+
+        if field.flatten {
+            bounds.push(syn::parse_quote!(#ty : #impl_trait));
+        } else if field.is_vec.is_some() {
+            // `Vec<T>` is a field only for some `T`
+            let field_ty = &field.ty;
+            bounds.push(syn::parse_quote!(#field_ty : ::typesensei::TypesenseField));
+        } else {
+            bounds.push(syn::parse_quote!(#ty : ::typesensei::TypesenseField));
         }
     }
 
-    if bounds.is_empty() {
-        return;
+    if !bounds.is_empty() {
+        generics.make_where_clause().predicates.extend(bounds);
     }
+}
 
-    if let Some(where_clause) = generics.where_clause.as_mut() {
-        where_clause.predicates.extend(bounds);
-    } else {
-        let mut predicates = Punctuated::new();
-        predicates.extend(bounds);
+/// Rule of serde's `rename_all`, erroring on the rule if it's unknown.
+pub fn rename_rule(rename_all: &Option<SpannedValue<Rename>>) -> Result<RenameRule> {
+    let Some(rename_all) = rename_all else {
+        return Ok(Default::default());
+    };
+    let Some(rule) = &rename_all.serialize else {
+        return Ok(Default::default());
+    };
 
-        generics.where_clause.replace(WhereClause {
-            where_token: Default::default(),
-            predicates,
-        });
+    RenameRule::from_str(rule).map_err(|e| syn::Error::new(rename_all.span(), e).into())
+}
+
+// invalid combinations of field options
+fn validate_fields(fields: &[Field]) -> Result<()> {
+    let mut errors = Error::accumulator();
+    let mut default_sorting_field: Option<&Field> = None;
+
+    for field in fields {
+        if is_object_array(field) && field.is_vec.is_none() {
+            errors.push(
+                Error::custom("`ty = \"object[]\"` requires a field of type `Vec<_>`")
+                    .with_span(&field.ty),
+            );
+        }
+
+        let is_custom = field.has_custom_serializer() && !field.schema && !field.flatten;
+        if is_custom && field.custom_type.is_none() && !field.timestamp && !field_is_id(field) {
+            errors.push(
+                Error::custom(
+                    "the typesense type of a custom serialized field can't be inferred, set it with `#[typesensei(ty = \"...\")]`, or `#[typesensei(timestamp)]` for dates serialized as epoch timestamps",
+                )
+                .with_span(&field.raw_ident),
+            );
+        }
+
+        // dates are serialized as strings or structs by default
+        if field.timestamp && !field.has_custom_serializer() {
+            errors.push(
+                Error::custom(
+                    "timestamp fields must be serialized as epoch timestamps, i.e. with `#[serde(with = \"typesensei::timestamp::seconds\")]`",
+                )
+                .with_span(&field.raw_ident),
+            );
+        }
+
+        // the partial's serializer functions couldn't infer the type parameter
+        let has_custom_serde = field.serializer().is_some() || field.deserializer().is_some();
+        if has_custom_serde && field.generic_type.is_some() && !field.flatten {
+            errors.push(
+                Error::custom("generic fields can't have a custom serializer").with_span(&field.ty),
+            );
+        }
 
-        return;
+        // flattened fields pass `facet` on to their own fields
+        let is_object = is_object(field) || is_object_array(field) || field.schema;
+        if is_object && field.facet == Some(true) {
+            errors.push(
+                Error::custom("object fields can't be faceted, facet their nested fields instead")
+                    .with_span(&field.raw_ident),
+            );
+        }
+
+        if !field.default_sorting_field {
+            continue;
+        }
+
+        if let Some(first) = default_sorting_field {
+            errors.push(
+                Error::custom(format!(
+                    "`{}` is already the default sorting field, a collection can only have one",
+                    first.raw_ident
+                ))
+                .with_span(&field.raw_ident),
+            );
+        } else {
+            default_sorting_field = Some(field);
+        }
+
+        if field.is_option.is_some() || field.optional == Some(true) {
+            errors.push(
+                Error::custom(
+                    "the default sorting field can't be optional, every document must have it",
+                )
+                .with_span(&field.raw_ident),
+            );
+        }
     }
+
+    errors.finish()
 }
 
-fn id_type(fields: &Vec<Field>) -> Result<Type> {
+// references are joined on `collection.field`
+fn validate_references(fields: &[Field]) -> Result<()> {
     for field in fields {
-        let Field { raw_ident, ty, .. } = field;
+        let Some(reference) = field.reference.as_ref() else {
+            continue;
+        };
 
-        if raw_ident == "id" {
-            return Ok(ty.clone());
+        let valid = reference
+            .split_once('.')
+            .map(|(collection, field)| !collection.is_empty() && !field.is_empty())
+            .unwrap_or(false);
+
+        if !valid {
+            return Err(Error::custom(format!(
+                "reference `{reference}` must be in the form `collection.field`"
+            ))
+            .with_span(&field.raw_ident));
         }
     }
 
-    syn::parse_str("String").map_err(|e| Error::custom(e))
+    Ok(())
+}
+
+// the field marked `#[typesensei(id)]`, or else the field serialized as `id`
+fn id_field(fields: &[Field]) -> Result<Option<Field>> {
+    let mut marked = fields.iter().filter(|f| f.id);
+
+    let Some(field) = marked.next() else {
+        return Ok(fields.iter().find(|f| field_is_id(f)).cloned());
+    };
+
+    if let Some(other) = marked.next() {
+        return Err(
+            Error::custom("only one field can be the document id").with_span(&other.raw_ident)
+        );
+    }
+
+    // typesense always stores the document id under `id`
+    if !field_is_id(field) {
+        return Err(Error::custom(
+            "the document id must be serialized as `id`, add `#[serde(rename = \"id\")]`",
+        )
+        .with_span(&field.raw_ident));
+    }
+
+    Ok(Some(field.clone()))
 }
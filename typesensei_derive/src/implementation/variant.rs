@@ -0,0 +1,84 @@
+use super::{Field, Rename};
+use darling::{ast::Fields, util::SpannedValue, Error, FromMeta, FromVariant};
+use proc_macro2::Span;
+use syn::{spanned::Spanned, Ident};
+
+/// Variant of an internally tagged or untagged enum document.
+#[derive(FromVariant, Clone)]
+#[darling(attributes(serde, typesensei))]
+pub struct Variant {
+    pub ident: Ident,
+    pub fields: Fields<Field>,
+    pub rename: Option<Rename>,
+    #[darling(multiple)]
+    pub alias: Vec<String>,
+    // renames the fields of the variant
+    pub rename_all: Option<SpannedValue<Rename>>,
+
+    // serde options that change how the variant is sent
+    skip: Option<Unsupported>,
+    skip_serializing: Option<Unsupported>,
+    skip_deserializing: Option<Unsupported>,
+    other: Option<Unsupported>,
+    untagged: Option<Unsupported>,
+    with: Option<Unsupported>,
+    serialize_with: Option<Unsupported>,
+    deserialize_with: Option<Unsupported>,
+    bound: Option<Unsupported>,
+    borrow: Option<Unsupported>,
+
+    // value of the tag field for this variant, set after parsing
+    #[darling(skip, default)]
+    pub tag_value: String,
+    // fields serde never sends
+    #[darling(skip, default)]
+    pub skipped: Vec<Field>,
+}
+
+impl Variant {
+    pub fn fields(&self) -> &Vec<Field> {
+        &self.fields.fields
+    }
+
+    /// Errors for the serde options enum documents can't store.
+    pub fn unsupported(&self) -> Vec<Error> {
+        let options = [
+            ("skip", &self.skip),
+            ("skip_serializing", &self.skip_serializing),
+            ("skip_deserializing", &self.skip_deserializing),
+            ("other", &self.other),
+            ("untagged", &self.untagged),
+            ("with", &self.with),
+            ("serialize_with", &self.serialize_with),
+            ("deserialize_with", &self.deserialize_with),
+            ("bound", &self.bound),
+            ("borrow", &self.borrow),
+        ];
+
+        options
+            .into_iter()
+            .filter_map(|(name, option)| {
+                option
+                    .as_ref()
+                    .map(|option| option.error(name, "variants of enum documents"))
+            })
+            .collect()
+    }
+}
+
+/// A serde option the derive doesn't support where it's used, accepted in any form
+/// so it can be rejected with a targeted error.
+#[derive(Clone, Copy)]
+pub struct Unsupported(Span);
+
+impl Unsupported {
+    pub fn error(&self, name: &str, place: &str) -> Error {
+        Error::custom(format!("serde's `{name}` is not supported on {place}")).with_span(&self.0)
+    }
+}
+
+impl FromMeta for Unsupported {
+    fn from_meta(item: &syn::Meta) -> darling::Result<Self> {
+        Ok(Self(item.path().span()))
+    }
+}
@@ -26,3 +26,15 @@ pub fn partial(input: TokenStream) -> TokenStream {
         Err(e) => e.write_errors().into(),
     }
 }
+
+/// For unit enums, stored in typesense as strings.
+#[proc_macro_derive(TypesenseField, attributes(typesensei))]
+#[proc_macro_error]
+pub fn typesense_field(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    match implementation::impl_typesense_field(input) {
+        Ok(field) => field.to_token_stream().into(),
+        Err(e) => e.write_errors().into(),
+    }
+}